@@ -0,0 +1,175 @@
+//! Options controlling how bindings are generated
+
+use std::cell::RefCell;
+use std::fmt;
+use std::io;
+use std::path::Path as FsPath;
+use std::str::FromStr;
+
+use serde::Deserialize;
+use syn::{parse_quote, Ident, Path};
+
+/// Name of the file in the crate root that naming & output conventions are read from
+pub const CONFIG_FILE: &str = "wasm-bindgen-ts-decl.toml";
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Bind globals with a known [web_sys] type as `thread_local_v2` statics
+    pub thread_local_globals: bool,
+    /// Emit compile time assertions that extern types implement `JsCast`
+    pub assert_js_cast: bool,
+    /// Bind functions documented with `@throws` using `catch`, returning a [Result]
+    pub catch_throws: bool,
+    /// Bind methods of classes that aren't abstract or extended in the same file as `final`
+    pub final_methods: bool,
+    /// Bind methods & accessors of interfaces as `structural`
+    pub structural_interfaces: bool,
+    /// Bind the contents of a file that only declares a namespace at the root of its module
+    pub flatten_sole_namespace: bool,
+    /// Pass typed arrays as slices & return them as [Vec]s instead of [js_sys] types
+    pub typed_array_slices: bool,
+    /// Return arrays as [Vec]s instead of boxed slices
+    pub vec_returns: bool,
+    /// Note the types bound as `JsValue` in the docs of the items using them
+    pub note_fallbacks: bool,
+    /// Skip declarations & members documented as `@internal`
+    pub strip_internal: bool,
+    /// JS module to import everything from instead of the global scope
+    pub js_module: Option<String>,
+    /// Path to the [wasm_bindgen] crate
+    pub wasm_bindgen_path: Path,
+    /// Version of [wasm_bindgen] to generate attributes for
+    pub target: Target,
+    /// Appended to the names of modules generated for files & namespaces
+    pub module_suffix: String,
+    /// Emit `js_name` even when it's the same as the Rust name
+    pub always_js_name: bool,
+    /// Path to the [web_sys] crate
+    pub web_sys_path: Path,
+    /// Path to the [js_sys] crate
+    pub js_sys_path: Path,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            thread_local_globals: false,
+            assert_js_cast: false,
+            catch_throws: false,
+            final_methods: false,
+            structural_interfaces: false,
+            flatten_sole_namespace: false,
+            typed_array_slices: false,
+            vec_returns: false,
+            note_fallbacks: false,
+            strip_internal: false,
+            js_module: None,
+            wasm_bindgen_path: parse_quote!(::wasm_bindgen),
+            target: Target::LATEST,
+            module_suffix: "Mod".to_string(),
+            always_js_name: false,
+            web_sys_path: parse_quote!(::web_sys),
+            js_sys_path: parse_quote!(::js_sys),
+        }
+    }
+}
+
+/// Version of [wasm_bindgen] to generate bindings for, since the attributes it supports change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Target(pub u32, pub u32, pub u32);
+
+impl Target {
+    /// The version generated for by default
+    pub const LATEST: Self = Self(0, 2, 100);
+
+    /// The attribute for thread local statics, if there is one
+    ///
+    /// `thread_local_v2` since 0.2.97, which replaced `thread_local` from 0.2.88.
+    pub fn thread_local(self) -> Option<Ident> {
+        if self >= Self(0, 2, 97) {
+            Some(parse_quote!(thread_local_v2))
+        } else if self >= Self(0, 2, 88) {
+            Some(parse_quote!(thread_local))
+        } else {
+            None
+        }
+    }
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("expected a version like `{}`, not `{s}`", Self::LATEST);
+        let parts = s
+            .split('.')
+            .map(|part| part.parse().map_err(|_| invalid()))
+            .collect::<Result<Vec<u32>, _>>()?;
+        match parts[..] {
+            [major, minor, patch] => Ok(Self(major, minor, patch)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.0, self.1, self.2)
+    }
+}
+
+/// Contents of [CONFIG_FILE], anything missing is left as is
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct ConfigFile {
+    module_suffix: Option<String>,
+    always_js_name: Option<bool>,
+    web_sys_path: Option<String>,
+    js_sys_path: Option<String>,
+}
+
+impl Config {
+    /// Apply the [CONFIG_FILE] in the crate root, if there is one
+    pub fn load_file(&mut self, crate_root: &FsPath) -> io::Result<()> {
+        let path = crate_root.join(CONFIG_FILE);
+        if !path.exists() {
+            return Ok(());
+        }
+        let invalid = |e: &dyn std::fmt::Display| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {e}", path.display()),
+            )
+        };
+
+        let file: ConfigFile =
+            toml::from_str(&std::fs::read_to_string(&path)?).map_err(|e| invalid(&e))?;
+        if let Some(module_suffix) = file.module_suffix {
+            self.module_suffix = module_suffix;
+        }
+        if let Some(always_js_name) = file.always_js_name {
+            self.always_js_name = always_js_name;
+        }
+        if let Some(web_sys_path) = file.web_sys_path {
+            self.web_sys_path = syn::parse_str(&web_sys_path).map_err(|e| invalid(&e))?;
+        }
+        if let Some(js_sys_path) = file.js_sys_path {
+            self.js_sys_path = syn::parse_str(&js_sys_path).map_err(|e| invalid(&e))?;
+        }
+        Ok(())
+    }
+}
+
+thread_local! {
+    static CONFIG: RefCell<Config> = RefCell::default();
+}
+
+/// Set the config used by conversions on this thread
+pub fn set_config(config: Config) {
+    CONFIG.with(|c| *c.borrow_mut() = config);
+}
+
+/// Access the config used by conversions on this thread
+pub fn config<R>(f: impl FnOnce(&Config) -> R) -> R {
+    CONFIG.with(|c| f(&c.borrow()))
+}
@@ -1,26 +1,41 @@
+use std::collections::HashMap;
+
+use log::warn;
+use swc_common::{Span, Spanned};
 use swc_ecma_ast::{
-    Accessibility, ClassDecl, ClassMember, ClassMethod, ClassProp, Constructor, Decl, FnDecl,
-    Function, Ident, MethodKind, Param, TsGetterSignature, TsInterfaceBody, TsInterfaceDecl,
-    TsMethodSignature, TsModuleBlock, TsModuleDecl, TsModuleName, TsNamespaceBody,
-    TsPropertySignature, TsSetterSignature, TsType, TsTypeAliasDecl, TsTypeAnn, TsTypeElement,
-    TsTypeLit,
+    Accessibility, ArrayPat, AssignPat, AssignPatProp, BigInt, BindingIdent, ClassDecl,
+    ClassMember, ClassMethod, ClassProp, ComputedPropName, Constructor, Decl, FnDecl, Function,
+    Ident, KeyValuePatProp, MethodKind, Number, ObjectPat, ObjectPatProp, Param,
+    ParamOrTsParamProp, Pat, PropName, RestPat, Str, TsArrayType, TsCallSignatureDecl,
+    TsConstructSignatureDecl, TsEntityName, TsEnumDecl, TsEnumMember, TsExprWithTypeArgs,
+    TsFnParam, TsGetterSignature, TsIndexSignature, TsInterfaceBody, TsInterfaceDecl,
+    TsIntersectionType, TsKeywordType, TsKeywordTypeKind, TsMethodSignature, TsModuleBlock,
+    TsModuleDecl, TsModuleName, TsNamespaceBody, TsParamProp, TsParamPropParam,
+    TsPropertySignature, TsQualifiedName, TsSetterSignature, TsTupleType, TsType, TsTypeAliasDecl,
+    TsTypeAnn, TsTypeElement, TsTypeLit, TsTypeParamDecl, TsTypeRef, TsUnionOrIntersectionType,
+    VarDeclarator,
 };
 use syn::{
-    parse_quote, parse_str,
-    punctuated::Punctuated,
-    token::{Brace, Comma},
-    visit_mut::VisitMut,
-    FnArg, ForeignItem, ForeignItemFn, ForeignItemType, Item, ItemMod, Pat, PatType, Signature,
-    Token, VisPublic, Visibility,
+    ext::IdentExt, parse_quote, parse_str, token::Brace, visit_mut::VisitMut, Attribute, Expr,
+    FnArg, ForeignItem, ForeignItemFn, ForeignItemStatic, ForeignItemType, Item, ItemMod, PatType,
+    ReturnType, Signature, Token, Type, TypePath, VisPublic, Visibility,
 };
 
 use crate::{
-    func::function_signature,
-    module::module_as_binding,
-    pat::pat_to_pat_type,
-    ty::{fn_param_to_pat, ts_type_to_type},
-    util::{sanitize_sym, ByeByeGenerics, ModuleBindingsCleaner},
-    wasm::js_value,
+    config::config,
+    func::{catch_throws, function_signature},
+    jsdoc::{is_internal, snippet},
+    module::{module_as_binding, Scope},
+    notes::{attach_notes, note},
+    pat::{params_to_fn_args, pat_to_pat_type},
+    stats::{count, skipped},
+    symbols::{declare_type, in_namespace, is_subclassed, namespace_scope, type_member},
+    ty::{fn_param_to_pat, ts_type_to_type, ClosuresToFunctions},
+    util::{
+        accessor_name, module_name, option_of, sanitize_sym, screaming_snake_case, ByeByeGenerics,
+        ModuleBindingsCleaner, KNOWN_WEB_SYS_TYPES,
+    },
+    wasm::{js_name_attr, js_value, wasm_bindgen_args, wasm_bindgen_attr},
 };
 
 /// Get the raw identifier for a declaration if any
@@ -45,32 +60,28 @@ pub fn decl_ident(decl: &Decl) -> Option<&str> {
 /// Convert classes, variables, type aliases, and interfaces to [ForeignItem]s.
 pub fn decl_to_items(decl: &Decl) -> Vec<ForeignItem> {
     match decl {
-        Decl::Class(class) => class_to_binding(class),
+        Decl::Class(class) => {
+            count(|s| s.classes += 1);
+            class_to_binding(class)
+        }
         Decl::Fn(FnDecl {
             ident: Ident { sym, .. },
             function,
             ..
         }) => {
+            count(|s| s.functions += 1);
             let name = sanitize_sym(sym);
             let sig = function_signature(&name, function);
+            let raw_name: &str = sym;
 
-            vec![parse_quote! {
+            let mut f: ForeignItemFn = parse_quote! {
                 pub #sig;
-            }]
-        }
-        Decl::Var(var) => {
-            assert!(var.decls.len() == 1);
-            let pat_type = pat_to_pat_type(&var.decls.first().unwrap().name);
-            let ident = if let Pat::Ident(ident) = pat_type.pat.as_ref() {
-                ident
-            } else {
-                unreachable!()
             };
-            vec![parse_quote! {
-                #[wasm_bindgen(js_name = #ident)]
-                pub static #pat_type;
-            }]
+            f.attrs.extend(js_name_attr(&name.to_string(), raw_name));
+            catch_throws(function.span.lo, &mut f);
+            vec![f.into()]
         }
+        Decl::Var(var) => var.decls.iter().flat_map(var_to_bindings).collect(),
         Decl::TsTypeAlias(t) => {
             let TsTypeAliasDecl {
                 id: Ident { sym, .. },
@@ -78,41 +89,81 @@ pub fn decl_to_items(decl: &Decl) -> Vec<ForeignItem> {
                 type_params,
                 ..
             } = t.as_ref();
-            let alias = ty_to_binding(sym);
+            let mut alias = ty_to_binding(sym);
             let name = alias.ident.clone();
-            let mut items = vec![alias.into()];
 
-            let mut cleaner = ByeByeGenerics::new(type_params.iter());
-            if let TsType::TsTypeLit(TsTypeLit { members, .. }) = type_ann.as_ref() {
-                items.append(&mut ty_elems_to_binding(
-                    &name,
-                    &mut cleaner,
-                    members.iter(),
-                ));
+            // i.e. `type Foo = Bar & { baz: number }` extends Bar & has baz
+            let parts: Vec<&TsType> = match type_ann.as_ref() {
+                TsType::TsUnionOrIntersectionType(
+                    TsUnionOrIntersectionType::TsIntersectionType(TsIntersectionType {
+                        types, ..
+                    }),
+                ) => types.iter().map(|ty| ty.as_ref()).collect(),
+                ty => vec![ty],
+            };
+            let mut members: Vec<&TsTypeElement> = vec![];
+            let mut sups = vec![];
+            for part in parts {
+                match part {
+                    TsType::TsTypeLit(TsTypeLit { members: lit, .. }) => members.extend(lit.iter()),
+                    // i.e. `type Alias = Real` extends Real, so they can be cast to each other
+                    TsType::TsTypeRef(_) => {
+                        let mut sup = ts_type_to_type(part);
+                        ByeByeGenerics::new(type_params.iter()).visit_type_mut(&mut sup);
+                        sups.push(sup);
+                    }
+                    _ => {}
+                }
             }
+            alias.attrs.extend(extends_attr(sups));
+
+            let mut cleaner = ByeByeGenerics::new(type_params.iter());
+            let mut items = vec![alias.into()];
+            items.append(&mut ty_elems_to_binding(
+                &name,
+                &mut cleaner,
+                members.into_iter(),
+            ));
+            add_js_class(&mut items, &name, sym);
             items
         }
         Decl::TsInterface(iface) => {
             let TsInterfaceDecl {
                 id: Ident { sym, .. },
                 type_params,
-                // TODO: extends
                 extends,
                 body: TsInterfaceBody { body, .. },
                 ..
             } = iface.as_ref();
-            let iface = ty_to_binding(sym);
+            count(|s| s.interfaces += 1);
+            let mut iface = ty_to_binding(sym);
+            let sups = extends
+                .iter()
+                .filter_map(|TsExprWithTypeArgs { span, expr, .. }| {
+                    let Some(type_name) = heritage_name(expr) else {
+                        warn!("Unsupported interface heritage of {sym}");
+                        return None;
+                    };
+                    Some(ts_type_to_type(&TsType::TsTypeRef(TsTypeRef {
+                        span: *span,
+                        type_name,
+                        type_params: None,
+                    })))
+                });
+            iface.attrs.extend(extends_attr(sups));
             let mut cleaner = ByeByeGenerics::new(type_params.iter());
             let mut elems = ty_elems_to_binding(&iface.ident, &mut cleaner, body.iter());
             elems
                 .iter_mut()
                 .for_each(|e| cleaner.visit_foreign_item_mut(e));
+            add_js_class(&mut elems, &iface.ident, sym);
             let mut items = vec![iface.into()];
             items.append(&mut elems);
             items
         }
+        // Needs to be handled separately since enums are exported rather than externed
         Decl::TsEnum(_) => {
-            todo!("{decl:?}")
+            vec![]
         }
         // Needs to be handled separately since we will create a mod for it
         Decl::TsModule(_) => {
@@ -121,19 +172,66 @@ pub fn decl_to_items(decl: &Decl) -> Vec<ForeignItem> {
     }
 }
 
+/// `extends` for the supertypes bound as extern types,
+/// rather than `JsValue`, primitives, `Box<[T]>` for arrays or Rust's `String`
+///
+/// They're kept in one attribute so they stay in order when attributes are merged,
+/// since the first is the one that's dereferenced to.
+fn extends_attr(sups: impl IntoIterator<Item = Type>) -> Option<Attribute> {
+    let sups: Vec<Type> = sups
+        .into_iter()
+        .filter(|sup| {
+            matches!(sup, Type::Path(TypePath { qself: None, path })
+                if path.leading_colon.is_none()
+                    && path.segments.iter().all(|seg| seg.arguments.is_empty()))
+                && *sup != parse_quote!(String)
+        })
+        .collect();
+    (!sups.is_empty()).then(|| parse_quote!(#[wasm_bindgen(#(extends = #sups),*)]))
+}
+
+/// Name of a type an interface extends, i.e. `ns.Base` for `ns.Base<string>`.
+/// The type arguments are dropped since `extends` only takes types.
+fn heritage_name(expr: &swc_ecma_ast::Expr) -> Option<TsEntityName> {
+    use swc_ecma_ast::{Expr, MemberExpr, MemberProp};
+    match expr {
+        Expr::Ident(ident) => Some(TsEntityName::Ident(ident.clone())),
+        Expr::Member(MemberExpr {
+            obj,
+            prop: MemberProp::Ident(right),
+            ..
+        }) => Some(TsEntityName::TsQualifiedName(Box::new(TsQualifiedName {
+            left: heritage_name(obj)?,
+            right: right.clone(),
+        }))),
+        _ => None,
+    }
+}
+
 pub fn ts_module_to_binding(module: &TsModuleDecl) -> Option<Item> {
-    let raw_name = match &module.id {
-        TsModuleName::Ident(i) => &i.sym,
-        TsModuleName::Str(s) => &s.value,
+    // The scope keeps the JS name for `js_namespace`, even if the module is renamed
+    let (name, scope) = match &module.id {
+        TsModuleName::Ident(i) => (sanitize_sym(&module_name(&i.sym)), Scope::Namespace(&i.sym)),
+        // i.e. `declare module "*.css"`, which isn't a module that can be imported
+        TsModuleName::Str(s) if s.value.contains('*') => {
+            warn!("Wildcard module declarations unsupported: {}", s.value);
+            skipped(module.span);
+            return None;
+        }
+        TsModuleName::Str(s) => (
+            sanitize_sym(&module_name(&js_module_raw_name(&s.value))),
+            Scope::Module(&s.value),
+        ),
     };
-    let name = sanitize_sym(raw_name);
 
     let items = match module.body.as_ref() {
-        Some(TsNamespaceBody::TsModuleBlock(TsModuleBlock { body, .. })) => {
-            module_as_binding(body, Some(raw_name))
-        }
+        Some(TsNamespaceBody::TsModuleBlock(TsModuleBlock { body, .. })) => match scope {
+            Scope::Namespace(ns) => in_namespace(ns, || module_as_binding(body, scope)),
+            _ => module_as_binding(body, scope),
+        },
         Some(TsNamespaceBody::TsNamespaceDecl(_)) => {
-            eprintln!("TS namespaces unsupported: {name}");
+            warn!("TS namespaces unsupported: {name}");
+            skipped(module.span);
             return None;
         }
         None => {
@@ -148,7 +246,7 @@ pub fn ts_module_to_binding(module: &TsModuleDecl) -> Option<Item> {
                 pub_token: <Token!(pub)>::default(),
             }),
             mod_token: <Token!(mod)>::default(),
-            ident: parse_str(&format!("{name}Mod")).unwrap(),
+            ident: name,
             content: Some((Brace::default(), items)),
             semi: None,
         }
@@ -156,6 +254,362 @@ pub fn ts_module_to_binding(module: &TsModuleDecl) -> Option<Item> {
     )
 }
 
+/// Name of the module for a `declare module "..."` block, before the module suffix
+///
+/// i.e. `declare module "pkg/sub"` is `pkg_sub`
+pub fn js_module_raw_name(specifier: &str) -> String {
+    specifier
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|seg| !seg.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Convert a declared variable to statics, one for each name it binds
+fn var_to_bindings(declarator: &VarDeclarator) -> Vec<ForeignItem> {
+    let member_ty = |ty: Option<&TsTypeAnn>, member: &str| -> Type {
+        match ty.and_then(|ty| type_member(&ty.type_ann, member)) {
+            Some((ty, false)) => ts_type_to_type(&ty),
+            Some((ty, true)) => option_of(ts_type_to_type(&ty)),
+            None => js_value().into(),
+        }
+    };
+
+    match &declarator.name {
+        swc_ecma_ast::Pat::Ident(BindingIdent { id, type_ann }) => {
+            match type_ann.as_ref().map(|ann| ann.type_ann.as_ref()) {
+                Some(TsType::TsTypeLit(TsTypeLit { members, .. })) => {
+                    type_lit_var_to_bindings(&id.sym, members)
+                }
+                _ => vec![static_binding(
+                    &id.sym,
+                    *pat_to_pat_type(&declarator.name).ty,
+                )],
+            }
+        }
+        // i.e. `declare const { a, b: c }: T` declares `a` & `c`
+        swc_ecma_ast::Pat::Object(ObjectPat {
+            props, type_ann, ..
+        }) => props
+            .iter()
+            .filter_map(|prop| {
+                let (member, raw_name): (&str, &str) = match prop {
+                    ObjectPatProp::Assign(AssignPatProp { key, .. }) => (&key.sym, &key.sym),
+                    ObjectPatProp::KeyValue(KeyValuePatProp { key, value }) => {
+                        match (key, value.as_ident()) {
+                            (PropName::Ident(Ident { sym: key, .. }), Some(value))
+                            | (PropName::Str(Str { value: key, .. }), Some(value)) => {
+                                (key, &value.id.sym)
+                            }
+                            _ => {
+                                warn!(
+                                    "Nested destructured variables are unsupported: {}",
+                                    snippet(declarator.span).unwrap_or_default()
+                                );
+                                return None;
+                            }
+                        }
+                    }
+                    ObjectPatProp::Rest(RestPat { arg, .. }) => {
+                        let raw_name: &str = &arg.as_ident()?.id.sym;
+                        return Some(static_binding(raw_name, js_value().into()));
+                    }
+                };
+                Some(static_binding(
+                    raw_name,
+                    member_ty(type_ann.as_deref(), member),
+                ))
+            })
+            .collect(),
+        // i.e. `declare const [a, b]: [A, B]`
+        swc_ecma_ast::Pat::Array(ArrayPat {
+            elems, type_ann, ..
+        }) => elems
+            .iter()
+            .enumerate()
+            .filter_map(|(i, elem)| {
+                let raw_name: &str = &elem.as_ref()?.as_ident()?.id.sym;
+                let ty = match type_ann.as_ref().map(|ann| ann.type_ann.as_ref()) {
+                    Some(TsType::TsTupleType(TsTupleType { elem_types, .. })) => elem_types
+                        .get(i)
+                        .map(|elem| ts_type_to_type(&elem.ty))
+                        .unwrap_or_else(|| js_value().into()),
+                    Some(TsType::TsArrayType(TsArrayType { elem_type, .. })) => {
+                        ts_type_to_type(elem_type)
+                    }
+                    _ => js_value().into(),
+                };
+                Some(static_binding(raw_name, ty))
+            })
+            .collect(),
+        _ => {
+            warn!(
+                "Unsupported variable declaration: {}",
+                snippet(declarator.span).unwrap_or_default()
+            );
+            vec![]
+        }
+    }
+}
+
+/// A variable typed with an object literal, i.e. `declare const config: { readonly port: 8080 }`,
+/// gets an extern type for its value named after it (`ConfigType`), numbered if that's taken
+///
+/// Only the variable's own literal is hoisted. Literals nested in it are `JsValue`s,
+/// as are `typeof` references back to the variable, so a recursive type can't expand forever.
+fn type_lit_var_to_bindings(raw_name: &str, members: &[TsTypeElement]) -> Vec<ForeignItem> {
+    let mut chars = raw_name.chars();
+    let base_name: String = chars
+        .next()
+        .into_iter()
+        .flat_map(char::to_uppercase)
+        .chain(chars)
+        .chain("Type".chars())
+        .collect();
+    // i.e. `ConfigType1` when there's already an `interface ConfigType`
+    let mut raw_type_name = base_name.clone();
+    let mut n = 0;
+    while !declare_type(&raw_type_name) {
+        n += 1;
+        raw_type_name = format!("{base_name}{n}");
+    }
+    let ty = ty_to_binding(&raw_type_name);
+    let name = ty.ident.clone();
+
+    let mut items = vec![ty.into()];
+    items.append(&mut ty_elems_to_binding(
+        &name,
+        &mut ByeByeGenerics::new(std::iter::empty()),
+        members.iter(),
+    ));
+    items.push(static_binding(raw_name, parse_quote!(#name)));
+    items
+}
+
+fn static_binding(raw_name: &str, mut ty: Type) -> ForeignItem {
+    ClosuresToFunctions.visit_type_mut(&mut ty);
+    if config(|c| c.thread_local_globals) && is_web_sys_type(&ty) {
+        match config(|c| c.target.thread_local()) {
+            Some(thread_local) => {
+                let name = screaming_snake_case(raw_name);
+                let mut s: ForeignItemStatic = parse_quote! {
+                    #[wasm_bindgen(#thread_local)]
+                    pub static #name: #ty;
+                };
+                s.attrs.extend(js_name_attr(&name.to_string(), raw_name));
+                return s.into();
+            }
+            None => warn!("Thread local statics need wasm-bindgen 0.2.88: {raw_name}"),
+        }
+    }
+    let name = sanitize_sym(raw_name);
+    let mut s: ForeignItemStatic = parse_quote! {
+        pub static #name: #ty;
+    };
+    s.attrs.extend(js_name_attr(&name.to_string(), raw_name));
+    s.into()
+}
+
+/// Value of an enum member
+#[derive(Debug, Clone, PartialEq)]
+enum EnumValue {
+    Num(i64),
+    Str(String),
+}
+
+/// Convert an enum to a [wasm_bindgen] enum, with members sharing a value bound as associated constants
+pub fn ts_enum_to_binding(
+    TsEnumDecl {
+        span, id, members, ..
+    }: &TsEnumDecl,
+) -> Vec<Item> {
+    let name = sanitize_sym(&id.sym);
+
+    let mut values: HashMap<String, EnumValue> = HashMap::new();
+    let mut variants: Vec<(syn::Ident, EnumValue)> = vec![];
+    let mut aliases: Vec<(syn::Ident, syn::Ident)> = vec![];
+    let mut next = 0;
+    for TsEnumMember {
+        id: member_id,
+        init,
+        ..
+    } in members
+    {
+        let raw_member: &str = member_id.as_ref();
+        let value = match init
+            .as_ref()
+            .and_then(|init| enum_init_value(&id.sym, init, &values))
+        {
+            Some(value) => value,
+            None => {
+                if init.is_some() {
+                    warn!(
+                        "Couldn't evaluate initializer of {}.{raw_member}, numbering it {next}",
+                        id.sym
+                    );
+                }
+                EnumValue::Num(next)
+            }
+        };
+        if let EnumValue::Num(n) = value {
+            next = n + 1;
+        }
+        values.insert(raw_member.to_string(), value.clone());
+
+        // i.e. `"a-b"` is bound as `a_b`. wasm_bindgen has no `js_name` for variants,
+        // but only the discriminant crosses over so the JS name isn't needed.
+        let member = sanitize_sym(raw_member);
+        match variants.iter().find(|(_, v)| *v == value) {
+            Some((existing, _)) => aliases.push((member, existing.clone())),
+            None => variants.push((member, value)),
+        }
+    }
+
+    if variants.is_empty() {
+        warn!("Empty enums can't be exported: {}", id.sym);
+        skipped(*span);
+        return vec![];
+    }
+    count(|s| s.enums += 1);
+    let discriminants: Vec<Expr> = match variants.first() {
+        Some((_, EnumValue::Num(_))) => variants
+            .iter()
+            .filter_map(|(_, v)| match v {
+                EnumValue::Num(n) => Some(parse_str(&n.to_string()).unwrap()),
+                EnumValue::Str(_) => None,
+            })
+            .collect(),
+        Some((_, EnumValue::Str(_))) => variants
+            .iter()
+            .filter_map(|(_, v)| match v {
+                EnumValue::Str(s) => Some(parse_quote!(#s)),
+                EnumValue::Num(_) => None,
+            })
+            .collect(),
+        None => vec![],
+    };
+    if discriminants.len() != variants.len() {
+        warn!(
+            "Enums mixing numbers and strings are unsupported: {}",
+            id.sym
+        );
+        return vec![];
+    }
+    let is_string_enum = matches!(variants.first(), Some((_, EnumValue::Str(_))));
+    let variants: Vec<_> = variants.iter().map(|(v, _)| v).collect();
+
+    let mut attr = wasm_bindgen_attr();
+    // Exported enums share one namespace in JS, so ones in namespaces are prefixed with them
+    let scope = namespace_scope();
+    if !scope.is_empty() {
+        let js_name = format!("{}_{}", scope.join("_"), id.sym);
+        let mut args = wasm_bindgen_args(&[attr]);
+        args.push(parse_quote!(js_name = #js_name));
+        attr = parse_quote!(#[wasm_bindgen(#(#args),*)]);
+    }
+    let mut items = vec![parse_quote! {
+        #attr
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum #name {
+            #(#variants = #discriminants),*
+        }
+    }];
+    // Converting in Rust, without a round trip through a JsValue
+    if is_string_enum {
+        items.push(parse_quote! {
+            impl #name {
+                pub fn from_js_string(s: &::core::primitive::str) -> ::std::option::Option<Self> {
+                    match s {
+                        #(#discriminants => ::std::option::Option::Some(Self::#variants),)*
+                        _ => ::std::option::Option::None,
+                    }
+                }
+
+                pub fn as_js_string(&self) -> &'static ::core::primitive::str {
+                    // wasm_bindgen adds a hidden variant for strings JS passes that aren't in the enum
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        #(Self::#variants => #discriminants,)*
+                        _ => ::core::panic!("invalid enum value passed"),
+                    }
+                }
+            }
+        });
+    }
+    if !aliases.is_empty() {
+        let (aliases, existing): (Vec<_>, Vec<_>) = aliases.into_iter().unzip();
+        items.push(parse_quote! {
+            impl #name {
+                #(
+                    #[allow(non_upper_case_globals)]
+                    pub const #aliases: #name = #name::#existing;
+                )*
+            }
+        });
+    }
+    items
+}
+
+/// Evaluate a constant enum member initializer,
+/// which may refer to earlier members of the enum
+fn enum_init_value(
+    enum_name: &str,
+    init: &swc_ecma_ast::Expr,
+    values: &HashMap<String, EnumValue>,
+) -> Option<EnumValue> {
+    use swc_ecma_ast::{BinaryOp, Expr, Lit, MemberExpr, MemberProp, UnaryOp};
+
+    let num = |expr: &Expr| match enum_init_value(enum_name, expr, values)? {
+        EnumValue::Num(n) => Some(n),
+        EnumValue::Str(_) => None,
+    };
+    Some(match init {
+        Expr::Lit(Lit::Num(n)) if n.value.fract() == 0. => EnumValue::Num(n.value as i64),
+        Expr::Lit(Lit::Str(s)) => EnumValue::Str(s.value.to_string()),
+        Expr::Paren(p) => enum_init_value(enum_name, &p.expr, values)?,
+        Expr::Ident(Ident { sym, .. }) => values.get(&**sym)?.clone(),
+        Expr::Member(MemberExpr {
+            obj,
+            prop: MemberProp::Ident(Ident { sym, .. }),
+            ..
+        }) if obj.as_ident().is_some_and(|i| &*i.sym == enum_name) => values.get(&**sym)?.clone(),
+        Expr::Unary(u) => {
+            let arg = num(&u.arg)?;
+            EnumValue::Num(match u.op {
+                UnaryOp::Minus => arg.checked_neg()?,
+                UnaryOp::Plus => arg,
+                UnaryOp::Tilde => !(arg as i32) as i64,
+                _ => return None,
+            })
+        }
+        Expr::Bin(b) => {
+            if let (BinaryOp::Add, Some(EnumValue::Str(l)), Some(EnumValue::Str(r))) = (
+                b.op,
+                enum_init_value(enum_name, &b.left, values),
+                enum_init_value(enum_name, &b.right, values),
+            ) {
+                return Some(EnumValue::Str(l + &r));
+            }
+            let (l, r) = (num(&b.left)?, num(&b.right)?);
+            // Bitwise operators work on 32-bit integers in JS
+            let shift = (r & 31) as u32;
+            EnumValue::Num(match b.op {
+                BinaryOp::Add => l.checked_add(r)?,
+                BinaryOp::Sub => l.checked_sub(r)?,
+                BinaryOp::Mul => l.checked_mul(r)?,
+                BinaryOp::LShift => (l as i32).wrapping_shl(shift) as i64,
+                BinaryOp::RShift => (l as i32).wrapping_shr(shift) as i64,
+                BinaryOp::ZeroFillRShift => (l as u32).wrapping_shr(shift) as i64,
+                BinaryOp::BitOr => ((l as i32) | (r as i32)) as i64,
+                BinaryOp::BitAnd => ((l as i32) & (r as i32)) as i64,
+                BinaryOp::BitXor => ((l as i32) ^ (r as i32)) as i64,
+                _ => return None,
+            })
+        }
+        _ => return None,
+    })
+}
+
 /// Convert class to its binding
 fn class_to_binding(
     ClassDecl {
@@ -181,7 +635,20 @@ fn class_to_binding(
     let class_name = clazz.ident.clone();
     items.push(clazz.into());
 
+    let mut index_signatures = vec![];
     for member in &class.body {
+        if is_internal(member.span_lo()) {
+            continue;
+        }
+        let first = items.len();
+        // Declared even when the class is abstract or the constructor is private
+        if let ClassMember::Constructor(constructor) = member {
+            items.extend(param_props_to_bindings(
+                &class_name,
+                &mut cleaner,
+                constructor,
+            ));
+        }
         match member {
             ClassMember::Method(ClassMethod { accessibility, .. })
             | ClassMember::Constructor(Constructor { accessibility, .. })
@@ -193,31 +660,51 @@ fn class_to_binding(
             {
                 continue;
             }
-            ClassMember::PrivateMethod(_) | ClassMember::PrivateProp(_) => {}
-            ClassMember::TsIndexSignature(_)
-            | ClassMember::Empty(_)
-            | ClassMember::StaticBlock(_) => todo!("{member:?}"),
-            ClassMember::Constructor(Constructor { key, params, .. }) => {
+            // Constructors can't be abstract themselves, but abstract classes can't be constructed
+            ClassMember::Constructor(_) if class.is_abstract => continue,
+            // `#private` members can't be used from outside the class, so they aren't bound,
+            // and `#x` doesn't get a public `x` renamed
+            ClassMember::PrivateMethod(_) | ClassMember::PrivateProp(_) => continue,
+            // Nothing to bind, but these can be in `.ts` sources
+            ClassMember::Empty(_) | ClassMember::StaticBlock(_) => {}
+            // Bound once the named members are, so they keep their names
+            ClassMember::TsIndexSignature(index_signature) => {
+                index_signatures.push(index_signature)
+            }
+            ClassMember::Constructor(Constructor {
+                span, key, params, ..
+            }) => {
                 let raw_name: &str = &key.as_ident().unwrap().sym;
                 let name = if raw_name == "constructor" {
                     parse_str("new").unwrap()
                 } else {
                     sanitize_sym(raw_name)
                 };
-                let mut syn_params: Punctuated<FnArg, Comma> = Punctuated::new();
-                for param in params.iter() {
-                    syn_params.push(FnArg::Typed(pat_to_pat_type(
-                        &param.as_param().unwrap().pat,
-                    )));
-                }
-                let mut sig = parse_quote! {
+                let pats: Vec<Pat> = params
+                    .iter()
+                    .map(|param| match param {
+                        ParamOrTsParamProp::Param(Param { pat, .. }) => pat.clone(),
+                        // i.e. `constructor(public x: number)` in `.ts` sources
+                        ParamOrTsParamProp::TsParamProp(TsParamProp { param, .. }) => match param {
+                            TsParamPropParam::Ident(ident) => Pat::Ident(ident.clone()),
+                            TsParamPropParam::Assign(assign) => Pat::Assign(assign.clone()),
+                        },
+                    })
+                    .collect();
+                let syn_params = params_to_fn_args(&pats);
+                let mut sig: Signature = parse_quote! {
                     fn #name(#syn_params) -> #class_name
                 };
                 cleaner.visit_signature_mut(&mut sig);
-                items.push(parse_quote! {
+                if has_constructor(&items) {
+                    overload_constructor_name(&mut sig);
+                }
+                let mut f: ForeignItemFn = parse_quote! {
                     #[wasm_bindgen(constructor)]
                     pub #sig;
-                });
+                };
+                catch_throws(span.lo, &mut f);
+                items.push(f.into());
             }
             ClassMember::Method(ClassMethod {
                 key,
@@ -226,12 +713,12 @@ fn class_to_binding(
                 is_static,
                 ..
             }) => {
-                if let Some(Ident { sym, .. }) = key.as_ident() {
+                if let Some(sym) = prop_name(key) {
                     items.push(
                         method_to_binding(
                             &class_name,
                             &mut cleaner,
-                            sym,
+                            &sym,
                             *kind,
                             *is_static,
                             function,
@@ -245,52 +732,229 @@ fn class_to_binding(
                 type_ann,
                 is_static,
                 is_optional,
+                readonly,
                 ..
             }) => {
-                if let Some(Ident { sym, .. }) = key.as_ident() {
-                    items.push(prop_to_binding(
+                if let Some(sym) = prop_name(key) {
+                    items.extend(prop_to_binding(
                         &class_name,
                         &mut cleaner,
-                        sym,
+                        &sym,
                         *is_static,
                         *is_optional,
+                        *readonly,
                         type_ann.as_ref().map(|b| b.as_ref()),
                     ));
                 }
             }
         }
+        attach_notes(&mut items[first..]);
+    }
+    for index_signature in index_signatures {
+        let mut indexing =
+            index_signature_to_bindings(&class_name, &mut cleaner, index_signature, &items);
+        attach_notes(&mut indexing);
+        items.append(&mut indexing);
+    }
+    if config(|c| c.final_methods) && !class.is_abstract && !is_subclassed(raw_class_name) {
+        // Nothing can override the methods, so skip the prototype chain lookup
+        add_to_methods(&mut items, parse_quote!(#[wasm_bindgen(final)]));
     }
+    add_js_class(&mut items, &class_name, raw_class_name);
 
     items
 }
 
+/// Properties declared by constructor params, i.e. `constructor(public x: number)` in `.ts`
+/// sources, unless they're private like other members
+fn param_props_to_bindings(
+    class_name: &syn::Ident,
+    cleaner: &mut ByeByeGenerics,
+    constructor: &Constructor,
+) -> Vec<ForeignItem> {
+    let mut items = vec![];
+    for param in &constructor.params {
+        let ParamOrTsParamProp::TsParamProp(TsParamProp {
+            accessibility,
+            readonly,
+            param,
+            ..
+        }) = param
+        else {
+            continue;
+        };
+        if matches!(
+            accessibility,
+            Some(Accessibility::Private | Accessibility::Protected)
+        ) {
+            continue;
+        }
+        let ident = match param {
+            TsParamPropParam::Ident(ident) => ident,
+            TsParamPropParam::Assign(AssignPat { left, .. }) => match left.as_ident() {
+                Some(ident) => ident,
+                None => continue,
+            },
+        };
+        items.extend(prop_to_binding(
+            class_name,
+            cleaner,
+            &ident.id.sym,
+            false,
+            ident.id.optional,
+            *readonly,
+            ident.type_ann.as_deref(),
+        ));
+    }
+    items
+}
+
+/// Whether a constructor has been bound already
+fn has_constructor(items: &[ForeignItem]) -> bool {
+    items.iter().any(|item| {
+        matches!(item, ForeignItem::Fn(f) if wasm_bindgen_args(&f.attrs).contains(&parse_quote!(constructor)))
+    })
+}
+
+/// Name an overloaded constructor after its params, like [web_sys] does, i.e. `new_with_x_and_y`
+///
+/// [wasm_bindgen] allows any number of constructors, but they need distinct Rust names.
+fn overload_constructor_name(sig: &mut Signature) {
+    let names: Option<Vec<_>> = sig
+        .inputs
+        .iter()
+        .map(|arg| match arg {
+            FnArg::Typed(PatType { pat, .. }) => match pat.as_ref() {
+                syn::Pat::Ident(pi) => Some(pi.ident.unraw().to_string()),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+    // Anything else is numbered by [ModuleBindingsCleaner]
+    if let Some(names) = names.filter(|names| !names.is_empty()) {
+        sig.ident = parse_str(&format!("{}_with_{}", sig.ident, names.join("_and_"))).unwrap();
+    }
+}
+
+/// Add an attribute to the methods & accessors in `items`, but not to indexing, which is done
+/// on the object itself
+fn add_to_methods(items: &mut [ForeignItem], attr: Attribute) {
+    let indexing = [
+        parse_quote!(indexing_getter),
+        parse_quote!(indexing_setter),
+        parse_quote!(indexing_deleter),
+    ];
+    for item in items {
+        if let ForeignItem::Fn(f) = item {
+            let args = wasm_bindgen_args(&f.attrs);
+            if args.contains(&parse_quote!(method))
+                && !indexing.iter().any(|arg| args.contains(arg))
+            {
+                f.attrs.push(attr.clone());
+            }
+        }
+    }
+}
+
+/// Tell [wasm_bindgen] the JS name of a class whose Rust name is different
+fn add_js_class(items: &mut [ForeignItem], class_name: &syn::Ident, raw_class_name: &str) {
+    if class_name == raw_class_name {
+        return;
+    }
+    for item in items {
+        if let ForeignItem::Fn(f) = item {
+            f.attrs
+                .push(parse_quote!(#[wasm_bindgen(js_class = #raw_class_name)]));
+        }
+    }
+}
+
 fn ty_elems_to_binding<'a>(
     name: &syn::Ident,
     class_cleaner: &mut ByeByeGenerics,
     elems: impl Iterator<Item = &'a TsTypeElement>,
 ) -> Vec<ForeignItem> {
     let mut items = vec![];
+    let mut index_signatures = vec![];
     for elem in elems {
+        if is_internal(elem.span_lo()) {
+            continue;
+        }
+        let first = items.len();
         match elem {
-            TsTypeElement::TsCallSignatureDecl(_) => todo!(),
-            TsTypeElement::TsConstructSignatureDecl(_) => todo!(),
+            TsTypeElement::TsCallSignatureDecl(TsCallSignatureDecl {
+                span,
+                params,
+                type_ann,
+                type_params,
+            }) => {
+                let fake_func = fake_function(*span, params, type_params, type_ann);
+                let mut cleaner = ByeByeGenerics::new(type_params.iter()).join(class_cleaner);
+                // Callable objects are functions, so go through Function.prototype.call
+                let mut call = method_to_binding(
+                    name,
+                    &mut cleaner,
+                    "call",
+                    MethodKind::Method,
+                    false,
+                    &fake_func,
+                );
+                let js_value = js_value();
+                call.sig.inputs.insert(1, parse_quote!(this_arg: #js_value));
+                items.push(call.into());
+            }
+            TsTypeElement::TsConstructSignatureDecl(TsConstructSignatureDecl {
+                span,
+                params,
+                type_ann,
+                type_params,
+            }) => {
+                let fake_func = fake_function(*span, params, type_params, type_ann);
+                let mut cleaner = ByeByeGenerics::new(type_params.iter()).join(class_cleaner);
+                let mut sig = function_signature(&parse_quote!(new), &fake_func);
+                cleaner.visit_signature_mut(&mut sig);
+                // i.e. `interface FooConstructor { new(): Foo }` constructs a Foo
+                let constructs_named_type = matches!(
+                    &sig.output,
+                    ReturnType::Type(_, ty) if matches!(
+                        ty.as_ref(),
+                        Type::Path(TypePath { qself: None, path }) if path.segments.len() == 1
+                    )
+                );
+                if !constructs_named_type {
+                    sig.output = parse_quote!(-> #name);
+                }
+                if has_constructor(&items) {
+                    overload_constructor_name(&mut sig);
+                }
+                let mut f: ForeignItemFn = parse_quote! {
+                    #[wasm_bindgen(constructor)]
+                    pub #sig;
+                };
+                catch_throws(span.lo, &mut f);
+                items.push(f.into());
+            }
             TsTypeElement::TsPropertySignature(TsPropertySignature {
                 key,
+                computed,
                 params,
                 type_ann,
                 type_params,
                 optional,
+                readonly,
                 ..
             }) => {
                 assert!(params.is_empty());
-                if let Some(Ident { sym, .. }) = key.as_ident() {
+                if let Some(sym) = key_name(key, *computed) {
                     let mut cleaner = ByeByeGenerics::new(type_params.iter()).join(class_cleaner);
-                    items.push(prop_to_binding(
+                    items.extend(prop_to_binding(
                         name,
                         &mut cleaner,
-                        sym,
+                        &sym,
                         false,
                         *optional,
+                        *readonly,
                         type_ann.as_ref().map(|b| b.as_ref()),
                     ));
                 }
@@ -298,6 +962,7 @@ fn ty_elems_to_binding<'a>(
             TsTypeElement::TsGetterSignature(TsGetterSignature {
                 span,
                 key,
+                computed,
                 type_ann,
                 ..
             }) => {
@@ -311,12 +976,12 @@ fn ty_elems_to_binding<'a>(
                     type_params: None,
                     return_type: type_ann.clone(),
                 };
-                if let Some(Ident { sym, .. }) = key.as_ident() {
+                if let Some(sym) = key_name(key, *computed) {
                     items.push(
                         method_to_binding(
                             name,
                             class_cleaner,
-                            sym,
+                            &sym,
                             MethodKind::Getter,
                             false,
                             &fake_func,
@@ -326,7 +991,11 @@ fn ty_elems_to_binding<'a>(
                 }
             }
             TsTypeElement::TsSetterSignature(TsSetterSignature {
-                span, key, param, ..
+                span,
+                key,
+                computed,
+                param,
+                ..
             }) => {
                 let fake_func = Function {
                     params: std::iter::once(param)
@@ -346,12 +1015,12 @@ fn ty_elems_to_binding<'a>(
                     type_params: None,
                     return_type: None,
                 };
-                if let Some(Ident { sym, .. }) = key.as_ident() {
+                if let Some(sym) = key_name(key, *computed) {
                     items.push(
                         method_to_binding(
                             name,
                             class_cleaner,
-                            sym,
+                            &sym,
                             MethodKind::Setter,
                             false,
                             &fake_func,
@@ -363,37 +1032,20 @@ fn ty_elems_to_binding<'a>(
             TsTypeElement::TsMethodSignature(TsMethodSignature {
                 span,
                 key,
+                computed,
                 params,
                 type_ann,
                 type_params,
                 ..
             }) => {
-                let fake_func = Function {
-                    params: params
-                        .iter()
-                        .cloned()
-                        .map(fn_param_to_pat)
-                        .map(|pat| Param {
-                            span: *span,
-                            decorators: vec![],
-                            pat,
-                        })
-                        .collect(),
-                    decorators: vec![],
-                    span: *span,
-                    body: None,
-                    is_generator: false,
-                    is_async: false,
-                    type_params: type_params.clone(),
-                    return_type: type_ann.clone(),
-                };
+                let fake_func = fake_function(*span, params, type_params, type_ann);
                 let mut cleaner = ByeByeGenerics::new(type_params.iter()).join(class_cleaner);
-                if let Some(Ident { sym, .. }) = key.as_ident() {
+                if let Some(sym) = key_name(key, *computed) {
                     items.push(
                         method_to_binding(
                             name,
                             &mut cleaner,
-                            sym,
+                            &sym,
                             MethodKind::Method,
                             false,
                             &fake_func,
@@ -402,20 +1054,129 @@ fn ty_elems_to_binding<'a>(
                     );
                 }
             }
-            TsTypeElement::TsIndexSignature(_) => {
-                eprintln!("Index signatures not supported");
+            // Bound once the named members are, so they keep their names
+            TsTypeElement::TsIndexSignature(index_signature) => {
+                index_signatures.push(index_signature)
             }
         }
+        attach_notes(&mut items[first..]);
+    }
+    for index_signature in index_signatures {
+        let mut indexing =
+            index_signature_to_bindings(name, class_cleaner, index_signature, &items);
+        attach_notes(&mut indexing);
+        items.append(&mut indexing);
+    }
+    // Interfaces describe shapes rather than prototypes
+    if config(|c| c.structural_interfaces) {
+        add_to_methods(&mut items, parse_quote!(#[wasm_bindgen(structural)]));
     }
 
-    let mut dedupe = ModuleBindingsCleaner::default();
-    items
-        .iter_mut()
-        .for_each(|i| dedupe.visit_foreign_item_mut(i));
+    ModuleBindingsCleaner::default().clean(&mut items);
 
     items
 }
 
+/// The JS name of a class member, for identifiers & numeric or string literal keys
+fn prop_name(key: &PropName) -> Option<String> {
+    match key {
+        PropName::Ident(Ident { sym, .. }) | PropName::Str(Str { value: sym, .. }) => {
+            Some(sym.to_string())
+        }
+        PropName::Num(Number { value, .. }) => Some(value.to_string()),
+        PropName::Computed(ComputedPropName { expr, .. }) => literal_key(expr),
+        PropName::BigInt(BigInt { span, .. }) => {
+            warn!("BigInt keys unsupported");
+            skipped(*span);
+            None
+        }
+    }
+}
+
+/// The JS name of a type element, i.e. `foo`, `0` or `"data-id"`
+fn key_name(key: &swc_ecma_ast::Expr, computed: bool) -> Option<String> {
+    use swc_ecma_ast::Expr;
+
+    match key {
+        Expr::Ident(Ident { sym, .. }) if !computed => Some(sym.to_string()),
+        _ => literal_key(key),
+    }
+}
+
+/// The JS name of a `[computed]` key, if it's a literal or a well-known iterator symbol
+fn literal_key(key: &swc_ecma_ast::Expr) -> Option<String> {
+    use swc_ecma_ast::{Expr, Lit, MemberExpr, MemberProp};
+
+    match key {
+        Expr::Lit(Lit::Str(Str { value, .. })) => Some(value.to_string()),
+        Expr::Lit(Lit::Num(Number { value, .. })) => Some(value.to_string()),
+        Expr::Member(MemberExpr {
+            obj,
+            prop: MemberProp::Ident(Ident { sym: symbol, .. }),
+            ..
+        }) if obj.as_ident().is_some_and(|i| &*i.sym == "Symbol") => {
+            // wasm_bindgen can only bind members by name, so iterators are bound by the names
+            // built in collections give them
+            let name = match &**symbol {
+                "iterator" => "values",
+                "asyncIterator" => "iter",
+                _ => {
+                    warn!("`[Symbol.{symbol}]` keys unsupported");
+                    skipped(key.span());
+                    return None;
+                }
+            };
+            note(format!("`[Symbol.{symbol}]` is bound as `{name}`"));
+            Some(name.to_string())
+        }
+        _ => {
+            warn!("Computed keys unsupported");
+            skipped(key.span());
+            None
+        }
+    }
+}
+
+/// The `this: &Class` receiver of a method or accessor
+fn self_receiver(class_name: &syn::Ident) -> FnArg {
+    FnArg::Typed(PatType {
+        attrs: vec![],
+        pat: Box::new(parse_quote!(this)),
+        colon_token: <Token!(:)>::default(),
+        ty: Box::new(parse_quote!(&#class_name)),
+    })
+}
+
+/// A bodiless [Function] for a signature-like type element
+fn fake_function(
+    span: Span,
+    params: &[TsFnParam],
+    type_params: &Option<Box<TsTypeParamDecl>>,
+    return_type: &Option<Box<TsTypeAnn>>,
+) -> Function {
+    Function {
+        params: params
+            .iter()
+            .cloned()
+            .map(fn_param_to_pat)
+            .map(|pat| Param {
+                span,
+                decorators: vec![],
+                pat,
+            })
+            .collect(),
+        decorators: vec![],
+        span,
+        body: None,
+        is_generator: false,
+        is_async: false,
+        type_params: type_params.clone(),
+        return_type: return_type.clone(),
+    }
+}
+
+/// Accessors are prefixed with `get_`/`set_`, so a getter & setter pair never collide
+/// when deduplicated, even if their types differ
 fn method_to_binding(
     class_name: &syn::Ident,
     cleaner: &mut ByeByeGenerics,
@@ -426,22 +1187,14 @@ fn method_to_binding(
 ) -> ForeignItemFn {
     let method_name = match kind {
         MethodKind::Method => sanitize_sym(raw_method_name),
-        MethodKind::Getter => sanitize_sym(&format!("get_{}", sanitize_sym(raw_method_name))),
-        MethodKind::Setter => sanitize_sym(&format!("set_{}", sanitize_sym(raw_method_name))),
+        MethodKind::Getter => accessor_name("get", raw_method_name),
+        MethodKind::Setter => accessor_name("set", raw_method_name),
     };
     let mut sig = function_signature(&method_name, function);
     cleaner.visit_signature_mut(&mut sig);
 
     if !is_static {
-        sig.inputs.insert(
-            0,
-            FnArg::Typed(PatType {
-                attrs: vec![],
-                pat: Box::new(parse_quote!(this)),
-                colon_token: <Token!(:)>::default(),
-                ty: Box::new(parse_quote!(&#class_name)),
-            }),
-        );
+        sig.inputs.insert(0, self_receiver(class_name));
     }
 
     let mut f: ForeignItemFn = parse_quote! {
@@ -450,40 +1203,150 @@ fn method_to_binding(
     f.attrs.push(if is_static {
         parse_quote!(#[wasm_bindgen(static_method_of = #class_name)])
     } else {
-        match kind {
-            MethodKind::Method => parse_quote!(#[wasm_bindgen(method)]),
-            MethodKind::Getter => parse_quote!(#[wasm_bindgen(method, getter)]),
-            MethodKind::Setter => parse_quote!(#[wasm_bindgen(method, setter)]),
-        }
+        parse_quote!(#[wasm_bindgen(method)])
     });
-    // if method_name != raw_method_name {
-    f.attrs
-        .push(parse_quote!(#[wasm_bindgen(js_name = #raw_method_name)]));
-    // }
+    match kind {
+        MethodKind::Method => {}
+        MethodKind::Getter => f.attrs.push(parse_quote!(#[wasm_bindgen(getter)])),
+        MethodKind::Setter => f.attrs.push(parse_quote!(#[wasm_bindgen(setter)])),
+    }
+    catch_throws(function.span.lo, &mut f);
+    let method_name = method_name.to_string();
+    let inferred = match kind {
+        MethodKind::Setter => method_name.strip_prefix("set_").unwrap_or(&method_name),
+        MethodKind::Method | MethodKind::Getter => &method_name,
+    };
+    f.attrs.extend(js_name_attr(inferred, raw_method_name));
 
     f
 }
 
+/// Whether the type is a single known [web_sys] type
+fn is_web_sys_type(ty: &Type) -> bool {
+    match ty {
+        Type::Path(TypePath { qself: None, path }) if path.segments.len() == 1 => {
+            KNOWN_WEB_SYS_TYPES.contains(path.segments[0].ident.to_string().as_str())
+        }
+        _ => false,
+    }
+}
+
 fn ty_to_binding(raw_name: &str) -> ForeignItemType {
     let name = sanitize_sym(raw_name);
     let mut ty: ForeignItemType = parse_quote! {
         pub type #name;
     };
-    // if name != raw_name {
-    ty.attrs
-        .push(parse_quote!(#[wasm_bindgen(js_name = #raw_name)]));
-    // }
+    ty.attrs.extend(js_name_attr(&name.to_string(), raw_name));
     ty
 }
 
+/// `indexing_getter`, and `indexing_setter` & `indexing_deleter` unless it's readonly,
+/// for an index signature like `[key: string]: number`
+///
+/// String keys are bound as `get`, `set` & `delete`, and number keys as `get_with_index` & co,
+/// like in [web_sys]. Names the other members already have get an `_indexed` suffix instead.
+fn index_signature_to_bindings(
+    class_name: &syn::Ident,
+    cleaner: &mut ByeByeGenerics,
+    TsIndexSignature {
+        params,
+        type_ann,
+        readonly,
+        is_static,
+        span,
+    }: &TsIndexSignature,
+    members: &[ForeignItem],
+) -> Vec<ForeignItem> {
+    let key_kind = match params.as_slice() {
+        [TsFnParam::Ident(BindingIdent {
+            type_ann: Some(ann),
+            ..
+        })] => match ann.type_ann.as_ref() {
+            TsType::TsKeywordType(TsKeywordType { kind, .. }) => Some(*kind),
+            _ => None,
+        },
+        _ => None,
+    };
+    let (key, suffix): (Type, &str) = match key_kind {
+        Some(TsKeywordTypeKind::TsStringKeyword) => (parse_quote!(&::core::primitive::str), ""),
+        Some(TsKeywordTypeKind::TsNumberKeyword) => {
+            (parse_quote!(::core::primitive::u32), "_with_index")
+        }
+        _ => {
+            warn!("Only string & number index signatures are supported");
+            skipped(*span);
+            return vec![];
+        }
+    };
+    if *is_static {
+        warn!("Static index signatures unsupported");
+        skipped(*span);
+        return vec![];
+    }
+
+    let mut ty = if let Some(ann) = type_ann {
+        ts_type_to_type(&ann.type_ann)
+    } else {
+        js_value().into()
+    };
+    ClosuresToFunctions.visit_type_mut(&mut ty);
+    let name = |op: &str| {
+        let mut name = format!("{op}{suffix}");
+        let taken = |name: &str| {
+            members
+                .iter()
+                .any(|m| matches!(m, ForeignItem::Fn(f) if f.sig.ident == name))
+        };
+        while taken(&name) {
+            name.push_str("_indexed");
+        }
+        sanitize_sym(&name)
+    };
+
+    let getter = name("get");
+    // Keys that aren't there are `undefined`
+    let getter_ty = option_of(ty.clone());
+    let mut accessors: Vec<(Signature, Attribute)> = vec![(
+        parse_quote!(fn #getter(key: #key) -> #getter_ty),
+        parse_quote!(#[wasm_bindgen(indexing_getter)]),
+    )];
+    if !readonly {
+        let setter = name("set");
+        let deleter = name("delete");
+        accessors.push((
+            parse_quote!(fn #setter(key: #key, value: #ty)),
+            parse_quote!(#[wasm_bindgen(indexing_setter)]),
+        ));
+        accessors.push((
+            parse_quote!(fn #deleter(key: #key)),
+            parse_quote!(#[wasm_bindgen(indexing_deleter)]),
+        ));
+    }
+    accessors
+        .into_iter()
+        .map(|(mut sig, kind)| {
+            cleaner.visit_signature_mut(&mut sig);
+            sig.inputs.insert(0, self_receiver(class_name));
+            let mut f: ForeignItemFn = parse_quote! {
+                #[wasm_bindgen(method, structural)]
+                pub #sig;
+            };
+            f.attrs.push(kind);
+            f.into()
+        })
+        .collect()
+}
+
+/// Getter, and setter unless it's readonly, for a property
 fn prop_to_binding(
     class_name: &syn::Ident,
     cleaner: &mut ByeByeGenerics,
     raw_prop_name: &str,
     is_static: bool,
     is_optional: bool,
+    readonly: bool,
     type_ann: Option<&TsTypeAnn>,
-) -> ForeignItem {
+) -> Vec<ForeignItem> {
     let prop_name = sanitize_sym(raw_prop_name);
     let mut ty = if let Some(ann) = type_ann {
         ts_type_to_type(&ann.type_ann)
@@ -491,24 +1354,43 @@ fn prop_to_binding(
         js_value().into()
     };
     if is_optional {
-        ty = parse_quote!(::std::option::Option<#ty>);
+        ty = option_of(ty);
     }
-    let mut sig: Signature = parse_quote! {
-        fn #prop_name(this: &#class_name) -> #ty
+    // The property keeps callbacks after the setter returns
+    ClosuresToFunctions.visit_type_mut(&mut ty);
+    // Imported getters already return owned values, even for `String`s.
+    // `getter_with_clone` is only for exported struct fields and is ignored (with a warning) here.
+    let getter: Signature = parse_quote! {
+        fn #prop_name() -> #ty
     };
-    cleaner.visit_signature_mut(&mut sig);
-
-    let mut f: ForeignItemFn = parse_quote! {
-        pub #sig;
-    };
-    f.attrs.push(if is_static {
-        parse_quote!(#[wasm_bindgen(static_method_of = #class_name)])
-    } else {
-        parse_quote!(#[wasm_bindgen(method)])
-    });
-    // if prop_name != raw_prop_name {
-    f.attrs
-        .push(parse_quote!(#[wasm_bindgen(js_name = #raw_prop_name)]));
-    // }
-    f.into()
+    let mut accessors: Vec<(Signature, Attribute)> =
+        vec![(getter, parse_quote!(#[wasm_bindgen(getter)]))];
+    if !readonly {
+        let setter_name = accessor_name("set", raw_prop_name);
+        let setter: Signature = parse_quote! {
+            fn #setter_name(value: #ty)
+        };
+        accessors.push((setter, parse_quote!(#[wasm_bindgen(setter)])));
+    }
+    accessors
+        .into_iter()
+        .map(|(mut sig, kind)| {
+            cleaner.visit_signature_mut(&mut sig);
+            if !is_static {
+                sig.inputs.insert(0, self_receiver(class_name));
+            }
+            let mut f: ForeignItemFn = parse_quote! {
+                pub #sig;
+            };
+            f.attrs.push(if is_static {
+                parse_quote!(#[wasm_bindgen(static_method_of = #class_name)])
+            } else {
+                parse_quote!(#[wasm_bindgen(method)])
+            });
+            f.attrs.push(kind);
+            f.attrs
+                .extend(js_name_attr(&prop_name.to_string(), raw_prop_name));
+            f.into()
+        })
+        .collect()
 }
@@ -1,13 +1,17 @@
-use swc_ecma_ast::{Function, TsKeywordType, TsKeywordTypeKind};
+use swc_common::BytePos;
+use swc_ecma_ast::Function;
 use syn::{
-    parse_quote, punctuated::Punctuated, token::Comma, visit_mut::VisitMut, FnArg, Ident,
-    ReturnType, Signature, Token,
+    parse_quote, visit_mut::VisitMut, FnArg, ForeignItemFn, Ident, Pat, PatType, ReturnType,
+    Signature, Token,
 };
 
 use crate::{
-    pat::pat_to_pat_type,
-    ty::ts_type_to_type,
+    config::config,
+    jsdoc::{jsdoc, jsdoc_integer, jsdoc_tags, NumberToInteger},
+    pat::params_to_fn_args,
+    ty::{returns_nothing, ts_type_to_type, ClosuresToFunctions},
     util::{sanitize_sym, ByeByeGenerics},
+    wasm::js_value,
 };
 
 pub fn function_signature(name: &Ident, function: &Function) -> Signature {
@@ -20,23 +24,16 @@ pub fn function_signature(name: &Ident, function: &Function) -> Signature {
         .collect();
     let mut generic_stripper = ByeByeGenerics(generics);
 
-    let mut params: Punctuated<FnArg, Comma> = Punctuated::new();
-    for param in function.params.iter() {
-        params.push(FnArg::Typed(pat_to_pat_type(&param.pat)));
-    }
+    let params = params_to_fn_args(function.params.iter().map(|param| &param.pat));
     let ret = function
         .return_type
         .as_ref()
-        .filter(|t| {
-            !matches!(
-                t.type_ann.as_ts_keyword_type(),
-                Some(TsKeywordType {
-                    kind: TsKeywordTypeKind::TsVoidKeyword,
-                    ..
-                })
-            )
-        })
+        .filter(|t| !returns_nothing(&t.type_ann))
         .map(|r| ts_type_to_type(&r.type_ann))
+        .map(|mut t| {
+            ClosuresToFunctions.visit_type_mut(&mut t);
+            t
+        })
         .map(|t| ReturnType::Type(<Token!(->)>::default(), Box::new(t)))
         .unwrap_or(ReturnType::Default);
 
@@ -44,5 +41,67 @@ pub fn function_signature(name: &Ident, function: &Function) -> Signature {
         fn #name (#params) #ret
     };
     generic_stripper.visit_signature_mut(&mut sig);
+    if let Some(doc) = jsdoc(function.span.lo) {
+        integer_tags(&doc, &mut sig);
+    }
     sig
 }
+
+/// Bind a function documented to throw using `catch`, returning a [Result]
+///
+/// Only with [Config::catch_throws](crate::Config::catch_throws) since most functions can throw.
+pub fn catch_throws(pos: BytePos, f: &mut ForeignItemFn) {
+    if !config(|c| c.catch_throws) {
+        return;
+    }
+    let throws = jsdoc(pos).is_some_and(|doc| {
+        jsdoc_tags(&doc).any(|tag| matches!(tag.name, "throws" | "throw" | "exception"))
+    });
+    if !throws {
+        return;
+    }
+    let ok = match &f.sig.output {
+        ReturnType::Type(_, ty) => ty.as_ref().clone(),
+        ReturnType::Default => parse_quote!(()),
+    };
+    let js_value = js_value();
+    f.sig.output = parse_quote!(-> ::std::result::Result<#ok, #js_value>);
+    f.attrs.push(parse_quote!(#[wasm_bindgen(catch)]));
+}
+
+/// Apply JSDoc `@param {int} x` & `@returns {int}` tags to `number`s in the signature
+fn integer_tags(doc: &str, sig: &mut Signature) {
+    for tag in jsdoc_tags(doc) {
+        let int = match tag.ty.and_then(jsdoc_integer) {
+            Some(int) => int,
+            None => continue,
+        };
+        match tag.name {
+            "param" => {
+                let param_name = tag
+                    .rest
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or_default()
+                    .trim_matches(|c| c == '[' || c == ']');
+                if param_name.is_empty() {
+                    continue;
+                }
+                let param_name = sanitize_sym(param_name);
+                for input in sig.inputs.iter_mut() {
+                    if let FnArg::Typed(PatType { pat, ty, .. }) = input {
+                        if matches!(pat.as_ref(), Pat::Ident(i) if i.ident == param_name) {
+                            NumberToInteger(int.clone()).visit_type_mut(ty);
+                        }
+                    }
+                }
+            }
+            "returns" | "return" => {
+                if let ReturnType::Type(_, ty) = &mut sig.output {
+                    NumberToInteger(int).visit_type_mut(ty);
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! Source & JSDoc lookup for the file being converted
+
+use std::cell::RefCell;
+use std::path::PathBuf;
+
+use swc_common::{
+    comments::{CommentKind, SingleThreadedComments},
+    sync::Lrc,
+    BytePos, FileName, SourceFile, Span,
+};
+use syn::{parse_quote, visit_mut::VisitMut, Type};
+
+use crate::config::config;
+
+/// Keywords that may sit between a JSDoc comment and the node it documents
+const MODIFIERS: &[&str] = &[
+    "export",
+    "declare",
+    "default",
+    "function",
+    "class",
+    "interface",
+    "type",
+    "enum",
+    "namespace",
+    "module",
+    "const",
+    "let",
+    "var",
+    "abstract",
+    "static",
+    "public",
+    "readonly",
+    "async",
+    "get",
+    "set",
+];
+
+thread_local! {
+    static COMMENTS: RefCell<Option<(Lrc<SourceFile>, SingleThreadedComments)>> = RefCell::default();
+}
+
+/// Set the source & comments of the file being converted
+pub fn set_comments(file: Lrc<SourceFile>, comments: SingleThreadedComments) {
+    COMMENTS.with(|c| *c.borrow_mut() = Some((file, comments)));
+}
+
+/// Get the JSDoc comment documenting the node starting at `pos`
+///
+/// Comments are keyed by the token after them, so this steps back over any [MODIFIERS] before
+/// `pos` (i.e. `export declare` before a function) to the token the comment is attached to.
+pub fn jsdoc(pos: BytePos) -> Option<String> {
+    COMMENTS.with(|c| {
+        let c = c.borrow();
+        let (file, comments) = c.as_ref()?;
+        let (leading, _) = comments.borrow_all();
+        if pos < file.start_pos {
+            return None;
+        }
+        let mut at = (pos - file.start_pos).0 as usize;
+        loop {
+            let doc = leading
+                .get(&(file.start_pos + BytePos(at as u32)))
+                .and_then(|comments| {
+                    comments
+                        .iter()
+                        .rev()
+                        .find(|c| c.kind == CommentKind::Block && c.text.starts_with('*'))
+                });
+            if let Some(doc) = doc {
+                return Some(doc.text.to_string());
+            }
+            let before = file.src.get(..at)?.trim_end();
+            let word_start = before
+                .trim_end_matches(|c: char| c.is_alphanumeric() || c == '_' || c == '$')
+                .len();
+            if !MODIFIERS.contains(&&before[word_start..]) {
+                return None;
+            }
+            at = word_start;
+        }
+    })
+}
+
+/// Whether the node starting at `pos` is documented as `@internal` & should be skipped
+///
+/// Only with [Config::strip_internal](crate::Config::strip_internal).
+pub fn is_internal(pos: BytePos) -> bool {
+    config(|c| c.strip_internal)
+        && jsdoc(pos).is_some_and(|doc| jsdoc_tags(&doc).any(|tag| tag.name == "internal"))
+}
+
+/// Get the source text of a node, with whitespace collapsed
+pub fn snippet(span: Span) -> Option<String> {
+    COMMENTS.with(|c| {
+        let c = c.borrow();
+        let (file, _) = c.as_ref()?;
+        let start = (span.lo - file.start_pos).0 as usize;
+        let end = (span.hi - file.start_pos).0 as usize;
+        file.src
+            .get(start..end)
+            .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+    })
+}
+
+/// 1-based line & column of a position in the file being converted
+pub fn line_column(pos: BytePos) -> Option<(usize, usize)> {
+    COMMENTS.with(|c| {
+        let c = c.borrow();
+        let (file, _) = c.as_ref()?;
+        if pos < file.start_pos || file.end_pos < pos {
+            return None;
+        }
+        let line = file.lookup_line(pos)?;
+        Some((line + 1, (pos - file.lines[line]).0 as usize + 1))
+    })
+}
+
+/// Path of the file being converted, unless it's not from a file
+pub fn source_path() -> Option<PathBuf> {
+    COMMENTS.with(|c| match &c.borrow().as_ref()?.0.name {
+        FileName::Real(path) => Some(path.clone()),
+        _ => None,
+    })
+}
+
+/// `/// <reference path="..." />` style directives in the file being converted, as
+/// `("path", "...")`, in order
+pub fn reference_directives() -> Vec<(String, String)> {
+    COMMENTS.with(|c| {
+        let c = c.borrow();
+        let Some((_, comments)) = c.as_ref() else {
+            return vec![];
+        };
+        let (leading, trailing) = comments.borrow_all();
+        let mut directives: Vec<_> = leading
+            .values()
+            .chain(trailing.values())
+            .flatten()
+            .filter(|c| c.kind == CommentKind::Line)
+            .filter_map(|c| Some((c.span.lo, reference_directive(&c.text)?)))
+            .collect();
+        directives.sort_by_key(|(at, _)| *at);
+        directives.into_iter().map(|(_, d)| d).collect()
+    })
+}
+
+/// `/// <reference path="..." />` paths in a file, found without parsing it
+pub fn reference_paths(src: &str) -> Vec<String> {
+    src.lines()
+        .filter_map(|line| reference_directive(line.trim().strip_prefix("//")?))
+        .filter(|(kind, _)| kind == "path")
+        .map(|(_, path)| path)
+        .collect()
+}
+
+/// The directive in the text of a line comment, after its `//`
+fn reference_directive(text: &str) -> Option<(String, String)> {
+    let attr = text
+        .strip_prefix('/')?
+        .trim()
+        .strip_prefix("<reference")?
+        .strip_suffix("/>")?;
+    let (kind, value) = attr.split_once('=')?;
+    let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+    Some((kind.trim().to_string(), value.to_string()))
+}
+
+/// A `@name {type} rest` JSDoc tag
+#[derive(Debug)]
+pub struct JsDocTag<'a> {
+    pub name: &'a str,
+    pub ty: Option<&'a str>,
+    pub rest: &'a str,
+}
+
+/// Iterate over the tags in a JSDoc comment
+///
+/// Tags start a line, so inline tags like `{@link Foo}` are left in the text around them.
+pub fn jsdoc_tags(doc: &str) -> impl Iterator<Item = JsDocTag<'_>> {
+    let mut starts = vec![];
+    let mut line_start = 0;
+    for line in doc.split_inclusive('\n') {
+        let content = line.trim_start_matches(|c: char| c.is_whitespace() || c == '*');
+        if content.starts_with('@') {
+            starts.push(line_start + line.len() - content.len());
+        }
+        line_start += line.len();
+    }
+    let ends: Vec<_> = starts.iter().skip(1).copied().chain([doc.len()]).collect();
+    starts.into_iter().zip(ends).map(|(start, end)| {
+        let tag = &doc[start + 1..end];
+        let (name, rest) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        let rest = rest.trim_start();
+        let (ty, rest) = match rest.strip_prefix('{').and_then(|r| r.split_once('}')) {
+            Some((ty, rest)) => (Some(ty.trim()), rest),
+            None => (None, rest),
+        };
+        JsDocTag {
+            name: name.trim_end_matches('*'),
+            ty,
+            rest: rest.trim_matches(|c: char| c.is_whitespace() || c == '*'),
+        }
+    })
+}
+
+/// The Rust integer type named by a JSDoc type, if any
+pub fn jsdoc_integer(ty: &str) -> Option<Type> {
+    match ty {
+        "int" | "integer" | "i32" => Some(parse_quote!(::core::primitive::i32)),
+        "long" | "i64" => Some(parse_quote!(::core::primitive::i64)),
+        _ => None,
+    }
+}
+
+/// The integer type a JSDoc comment on a value asks for via `@type {int}` or `@integer`
+pub fn jsdoc_value_integer(doc: &str) -> Option<Type> {
+    jsdoc_tags(doc).find_map(|tag| match tag.name {
+        "type" => tag.ty.and_then(jsdoc_integer),
+        "integer" => jsdoc_integer("integer"),
+        _ => None,
+    })
+}
+
+/// Replaces `f64` with the given integer type
+pub struct NumberToInteger(pub Type);
+
+impl VisitMut for NumberToInteger {
+    fn visit_type_mut(&mut self, t: &mut Type) {
+        if *t == parse_quote!(::core::primitive::f64) {
+            *t = self.0.clone();
+        } else {
+            syn::visit_mut::visit_type_mut(self, t);
+        }
+    }
+}
@@ -0,0 +1,298 @@
+//! Generate low-level Rust bindings to JavaScript using [wasm_bindgen] & Typescript declarations
+
+use std::io::{self, Write};
+use std::path::Path;
+
+use swc_common::{
+    comments::SingleThreadedComments,
+    errors::{ColorConfig, Handler},
+    sync::Lrc,
+    FileName, SourceFile, SourceMap,
+};
+use swc_ecma_ast::{Module, ModuleItem};
+use swc_ecma_parser::{lexer::Lexer, Parser, StringInput, Syntax, TsConfig};
+use syn::ext::IdentExt;
+use syn::visit::Visit;
+use syn::visit_mut::VisitMut;
+use syn::{parse_quote, Expr, ExprAssign, ForeignItem, Item};
+
+use crate::jsdoc::{reference_directives, set_comments};
+use crate::module::{
+    apply_js_module, imports_to_uses, module_as_binding, references_to_uses, sole_namespace,
+    sole_namespace_as_binding, Scope,
+};
+use crate::stats::count;
+use crate::symbols::collect_symbols;
+use crate::ty::wasm_abi_set;
+use crate::util::{
+    add_js_cast_assertions, remove_unused_uses, BindingsCleaner, CollectPubs, SysUseAdder,
+    TypedArraySlices, VecReturns, WasmAbify,
+};
+use crate::wasm::wasm_bindgen_args;
+
+pub use crate::config::{config, set_config, Config, Target};
+pub use crate::jsdoc::reference_paths;
+pub use crate::stats::{take_stats, Construct, Stats};
+
+mod config;
+mod decl;
+mod func;
+mod jsdoc;
+mod module;
+mod notes;
+mod pat;
+mod stats;
+mod symbols;
+mod ty;
+mod util;
+mod wasm;
+
+/// Parse & convert a Typescript declaration file
+pub fn convert_dts_file(path: &Path) -> io::Result<syn::File> {
+    let cm: Lrc<SourceMap> = Default::default();
+    let fm = cm.load_file(path)?;
+    convert_source_file(cm, fm, true)
+}
+
+/// Parse & convert Typescript declarations
+pub fn convert_dts_source(src: &str) -> io::Result<syn::File> {
+    let cm: Lrc<SourceMap> = Default::default();
+    let fm = cm.new_source_file(FileName::Anon, src.to_string());
+    convert_source_file(cm, fm, true)
+}
+
+/// Parse & convert Typescript declarations, writing the formatted bindings to `out`
+pub fn convert_dts_to_writer(src: &str, out: &mut dyn Write) -> io::Result<()> {
+    let file = convert_dts_source(src)?;
+    out.write_all(prettyplease::unparse(&file).as_bytes())
+}
+
+/// Parse a Typescript source file & convert its declarations, skipping any runtime statements
+pub fn convert_ts_file(path: &Path) -> io::Result<syn::File> {
+    let cm: Lrc<SourceMap> = Default::default();
+    let fm = cm.load_file(path)?;
+    convert_source_file(cm, fm, false)
+}
+
+/// Parse a declaration file for its items alone, i.e. one referenced by the file being converted
+pub(crate) fn parse_dts_file(path: &Path) -> io::Result<Module> {
+    let cm: Lrc<SourceMap> = Default::default();
+    let fm = cm.load_file(path)?;
+    parse(cm, &fm, true, &SingleThreadedComments::default())
+}
+
+fn parse(
+    cm: Lrc<SourceMap>,
+    fm: &SourceFile,
+    dts: bool,
+    comments: &SingleThreadedComments,
+) -> io::Result<Module> {
+    let handler = Handler::with_tty_emitter(ColorConfig::Auto, true, false, Some(cm));
+    // JSX only parses in `.tsx` files, where `<T>expr` type assertions don't
+    let tsx = matches!(&fm.name, FileName::Real(path) if path.extension().is_some_and(|ext| ext == "tsx"));
+
+    let lexer = Lexer::new(
+        Syntax::Typescript(TsConfig {
+            dts,
+            tsx,
+            ..Default::default()
+        }),
+        Default::default(),
+        StringInput::from(fm),
+        Some(comments),
+    );
+
+    let mut parser = Parser::new_from(lexer);
+
+    for e in parser.take_errors() {
+        e.into_diagnostic(&handler).emit();
+    }
+
+    parser.parse_module().map_err(|e| {
+        // Unrecoverable fatal error occurred
+        e.into_diagnostic(&handler).emit();
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("failed to parse {}", fm.name),
+        )
+    })
+}
+
+fn convert_source_file(
+    cm: Lrc<SourceMap>,
+    fm: Lrc<SourceFile>,
+    dts: bool,
+) -> io::Result<syn::File> {
+    let comments = SingleThreadedComments::default();
+    let mut module = parse(cm, &fm, dts, &comments)?;
+    set_comments(fm, comments);
+    count(|s| s.files += 1);
+    if !dts {
+        module
+            .body
+            .retain(|item| !matches!(item, ModuleItem::Stmt(stmt) if !stmt.is_decl()));
+    }
+
+    Ok(convert_module(&module))
+}
+
+/// Convert a parsed Typescript module
+pub fn convert_module(module: &Module) -> syn::File {
+    let mut file: syn::File = syn::File {
+        shebang: None,
+        attrs: vec![],
+        items: vec![],
+    };
+
+    let sole_namespace =
+        sole_namespace(&module.body).filter(|_| config(|c| c.flatten_sole_namespace));
+    collect_symbols(sole_namespace.map_or(&module.body, |(_, body)| body));
+    let mut uses = imports_to_uses(&module.body);
+    uses.append(&mut references_to_uses(&module.body));
+    let mut module_items = match sole_namespace {
+        Some((raw_name, body)) => sole_namespace_as_binding(raw_name, body),
+        None => module_as_binding(&module.body, Scope::File),
+    };
+    if let Some(js_module) = config(|c| c.js_module.clone()) {
+        apply_js_module(&mut module_items, &js_module);
+    }
+
+    let mut cleaner = BindingsCleaner;
+    module_items
+        .iter_mut()
+        .for_each(|i| cleaner.visit_item_mut(i));
+
+    let mut pubs = CollectPubs::default();
+    module_items.iter().for_each(|i| pubs.visit_item(i));
+    uses.iter().for_each(|u| pubs.visit_item_use(u));
+
+    if config(|c| c.typed_array_slices) {
+        let mut slices = TypedArraySlices {
+            pubs: pubs.0.clone(),
+        };
+        module_items
+            .iter_mut()
+            .for_each(|i| slices.visit_item_mut(i));
+    }
+
+    if config(|c| c.vec_returns) {
+        module_items
+            .iter_mut()
+            .for_each(|i| VecReturns.visit_item_mut(i));
+    }
+
+    // All externed types implement JsObject
+    // so they can be directly sent back to JS.
+    let mut abify = WasmAbify {
+        wasm_abi_types: wasm_abi_set(&pubs.0),
+    };
+    module_items
+        .iter_mut()
+        .for_each(|i| abify.visit_item_mut(i));
+    let mut adder = SysUseAdder {
+        pubs: pubs.0,
+        uses: vec![],
+    };
+    module_items.iter().for_each(|i| adder.visit_item(i));
+
+    // i.e. `/// <reference types="node" />`, which can't be resolved from here
+    for (kind, types) in reference_directives() {
+        if kind == "types" {
+            let doc = format!(" Uses the `{types}` types");
+            file.attrs.push(parse_quote!(#![doc = #doc]));
+        }
+    }
+
+    file.items.extend(adder.uses.into_iter().map(Item::Use));
+    file.items.extend(uses.into_iter().map(Item::Use));
+    file.items.append(&mut module_items);
+    if config(|c| c.assert_js_cast) {
+        add_js_cast_assertions(&mut file.items);
+    }
+    remove_unused_uses(&mut file.items);
+
+    file
+}
+
+/// Number of bindings (functions, statics, and types) in converted declarations
+pub fn binding_count(file: &syn::File) -> usize {
+    struct CountBindings(usize);
+    impl<'ast> Visit<'ast> for CountBindings {
+        fn visit_foreign_item(&mut self, _: &'ast syn::ForeignItem) {
+            self.0 += 1;
+        }
+    }
+
+    let mut counter = CountBindings(0);
+    counter.visit_file(file);
+    counter.0
+}
+
+/// Names of the types, functions, statics, and enums at the root of converted declarations,
+/// leaving out members of types & anything in nested modules
+pub fn binding_names(file: &syn::File) -> Vec<syn::Ident> {
+    let mut names = vec![];
+    for item in &file.items {
+        match item {
+            Item::ForeignMod(ifm) => {
+                for fi in &ifm.items {
+                    match fi {
+                        ForeignItem::Fn(f) => {
+                            let is_member =
+                                wasm_bindgen_args(&f.attrs).iter().any(|arg| match arg {
+                                    Expr::Path(_) => {
+                                        *arg == parse_quote!(method)
+                                            || *arg == parse_quote!(constructor)
+                                    }
+                                    Expr::Assign(ExprAssign { left, .. }) => {
+                                        **left == parse_quote!(static_method_of)
+                                    }
+                                    _ => false,
+                                });
+                            if !is_member {
+                                names.push(f.sig.ident.clone());
+                            }
+                        }
+                        ForeignItem::Static(s) => names.push(s.ident.clone()),
+                        ForeignItem::Type(t) => names.push(t.ident.clone()),
+                        _ => {}
+                    }
+                }
+            }
+            Item::Enum(ie) => names.push(ie.ident.clone()),
+            _ => {}
+        }
+    }
+    names
+}
+
+/// JS names of the enums exported by converted declarations, including ones in nested modules
+pub fn exported_enum_names(file: &syn::File) -> Vec<String> {
+    struct EnumNames(Vec<String>);
+    impl<'ast> Visit<'ast> for EnumNames {
+        fn visit_item_enum(&mut self, ie: &'ast syn::ItemEnum) {
+            let js_name = wasm_bindgen_args(&ie.attrs)
+                .into_iter()
+                .find_map(|arg| match arg {
+                    Expr::Assign(ExprAssign { left, right, .. })
+                        if *left == parse_quote!(js_name) =>
+                    {
+                        match *right {
+                            Expr::Lit(syn::ExprLit {
+                                lit: syn::Lit::Str(s),
+                                ..
+                            }) => Some(s.value()),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                });
+            self.0
+                .push(js_name.unwrap_or_else(|| ie.ident.unraw().to_string()));
+        }
+    }
+
+    let mut names = EnumNames(vec![]);
+    names.visit_file(file);
+    names.0
+}
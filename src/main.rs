@@ -1,50 +1,416 @@
-use std::collections::{HashMap, HashSet};
-use std::fs::{File, OpenOptions};
-use std::io::Write as IoWrite;
-use std::{env::args, path::PathBuf};
-
-use swc_common::{
-    errors::{ColorConfig, Handler},
-    sync::Lrc,
-    SourceMap,
-};
-use swc_ecma_parser::{lexer::Lexer, Parser, StringInput, Syntax, TsConfig};
-use syn::visit::Visit;
-use syn::visit_mut::VisitMut;
-use syn::Item;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write as FmtWrite;
+use std::fs::File;
+use std::io::{Read, Write as IoWrite};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{error::ErrorKind, ArgAction, CommandFactory, Parser as ClapParser};
+use globset::{Glob, GlobSetBuilder};
+use log::{error, info, warn, LevelFilter};
+use syn::{parse_quote, Ident, Item, ItemUse};
 use walkdir::WalkDir;
 
-use crate::module::{imports_to_uses, module_as_binding};
-use crate::ty::wasm_abi_set;
-use crate::util::{BindingsCleaner, CollectPubs, SysUseAdder, WasmAbify};
-
-mod decl;
-mod func;
-mod module;
-mod pat;
-mod ty;
-mod util;
-mod wasm;
-
-fn main() -> std::io::Result<()> {
-    let typescript_path = PathBuf::from(args().nth(1).expect("No dir specified"));
-    let rust_destination = PathBuf::from(args().nth(2).expect("No dest specified"));
-
-    let mut crate_path = typescript_path.as_path();
-    while let Some(parent) = crate_path.parent() {
-        if crate_path.join("Cargo.toml").exists() {
-            break;
+use wasm_bindgen_ts_decl::{
+    binding_count, binding_names, convert_dts_file, convert_dts_to_writer, convert_ts_file,
+    exported_enum_names, reference_paths, set_config, take_stats, Config, Target,
+};
+
+#[derive(ClapParser, Debug)]
+#[command(about, version)]
+struct Args {
+    /// Directory containing Typescript declarations, or `-` to convert a declaration file from
+    /// stdin to stdout
+    typescript_path: PathBuf,
+    /// Directory to write the Rust bindings to, unless converting from stdin
+    rust_destination: Option<PathBuf>,
+    /// Bind globals with a known web_sys type as `thread_local_v2` statics
+    #[arg(long)]
+    thread_local_globals: bool,
+    /// Emit compile time assertions that extern types implement JsCast
+    #[arg(long)]
+    assert_js_cast: bool,
+    /// Bind functions documented with `@throws` using `catch`, returning a `Result`
+    #[arg(long)]
+    catch_throws: bool,
+    /// Bind methods of classes that aren't abstract or extended in the same file as `final`
+    #[arg(long)]
+    final_methods: bool,
+    /// Bind methods & accessors of interfaces as `structural`, looking them up as properties
+    #[arg(long)]
+    structural_interfaces: bool,
+    /// Bind the contents of files that only declare a namespace at the root of their module
+    #[arg(long)]
+    flatten_sole_namespace: bool,
+    /// Pass typed arrays as slices & return them as `Vec`s, copying them to & from JS
+    #[arg(long)]
+    typed_array_slices: bool,
+    /// Return arrays as `Vec`s instead of boxed slices
+    #[arg(long)]
+    vec_returns: bool,
+    /// Note the types bound as `JsValue` in the docs of the items using them
+    #[arg(long)]
+    note_fallbacks: bool,
+    /// Skip declarations & members documented as `@internal`
+    #[arg(long)]
+    strip_internal: bool,
+    /// JS module to import the bindings from instead of the global scope, i.e. `./dist/lib.js`
+    #[arg(long = "module")]
+    js_module: Option<String>,
+    /// Path to the wasm_bindgen crate, for when it's re-exported by another crate
+    #[arg(long, default_value = "::wasm_bindgen", value_parser = parse_path)]
+    wasm_bindgen_path: String,
+    /// Version of wasm_bindgen to generate attributes for, i.e. `0.2.87` has no thread local statics
+    #[arg(long, default_value_t = Target::LATEST)]
+    target: Target,
+    /// Convert everything but only report what would be written
+    #[arg(long)]
+    dry_run: bool,
+    /// Name of the module file listing the top-level modules of the destination, i.e. `lib.rs`
+    #[arg(long, default_value = "mod.rs")]
+    root_module: String,
+    /// Write all bindings to the destination file, with inline modules mirroring the directories
+    #[arg(long)]
+    single_file: bool,
+    /// Skip files & directories matching a glob, relative to the declarations, i.e. `**/*.test.d.ts`
+    #[arg(long, value_parser = Glob::new)]
+    exclude: Vec<Glob>,
+    /// Also convert the declarations in `.ts` sources, skipping runtime statements
+    #[arg(long)]
+    ts_sources: bool,
+    /// Crate to read the config file from, by default the one containing the declarations,
+    /// or the destination if they aren't in one
+    #[arg(long)]
+    crate_root: Option<PathBuf>,
+    /// Convert every file, even the ones that haven't changed since the last run
+    #[arg(long)]
+    force: bool,
+    /// Print how many declarations were bound, & which types & members couldn't be
+    #[arg(long)]
+    stats: bool,
+    /// Add a `prelude` module to the top-level module, re-exporting the bindings at the root of
+    /// every file
+    #[arg(long)]
+    prelude: bool,
+    /// Log more, repeat for even more (-v for unsupported constructs, -vv for progress)
+    #[arg(short, long, action = ArgAction::Count)]
+    verbose: u8,
+}
+
+/// Check that a crate path is a valid Rust path, keeping it as a string since paths aren't `Send`
+fn parse_path(s: &str) -> Result<String, syn::Error> {
+    syn::parse_str::<syn::Path>(s)?;
+    Ok(s.to_string())
+}
+
+/// Modules for `--single-file`, nested like the directories they came from
+#[derive(Default)]
+struct ModTree {
+    items: Vec<Item>,
+    children: BTreeMap<String, ModTree>,
+}
+
+impl ModTree {
+    /// Add the bindings for a file, by its path relative to the input directory
+    fn insert(&mut self, path: &Path, file: syn::File) {
+        let mut node = self;
+        for dir in path.parent().into_iter().flat_map(Path::iter) {
+            node = node
+                .children
+                .entry(dir.to_str().unwrap().to_string())
+                .or_default();
+        }
+        let filename = path.file_name().unwrap().to_str().unwrap();
+        let filename = filename.split_once('.').unwrap().0;
+        node.children
+            .entry(filename.to_string())
+            .or_default()
+            .items
+            .extend(file.items);
+    }
+
+    fn into_items(self, module_suffix: &str) -> Vec<Item> {
+        let mut items = self.items;
+        for (name, child) in self.children {
+            let content = child.into_items(module_suffix);
+            if content.is_empty() {
+                continue;
+            }
+            let ident: Ident = syn::parse_str(&format!("{name}{module_suffix}")).unwrap();
+            items.push(parse_quote! {
+                #[allow(non_snake_case)]
+                pub mod #ident {
+                    #(#content)*
+                }
+            });
+        }
+        items
+    }
+}
+
+/// Hashes of the declarations each file in the destination was converted from, by its path
+/// relative to the destination, so unchanged ones can be skipped
+const MANIFEST_FILE: &str = ".wasm-bindgen-ts-decl";
+
+fn read_manifest(path: &Path) -> HashMap<String, u64> {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return HashMap::new();
+    };
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (hash, file) = line.split_once(' ')?;
+            Some((file.to_string(), u64::from_str_radix(hash, 16).ok()?))
+        })
+        .collect()
+}
+
+fn write_manifest(path: &Path, manifest: &BTreeMap<String, u64>) -> std::io::Result<()> {
+    let mut contents = String::new();
+    for (file, hash) in manifest {
+        writeln!(contents, "{hash:016x} {file}").unwrap();
+    }
+    write_if_changed(path, &contents)
+}
+
+/// 64-bit FNV-1a of each of `parts` in turn, which unlike [std::hash::DefaultHasher] is the same
+/// for every build, so the manifest stays valid
+fn fnv1a(parts: &[&[u8]]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for part in parts {
+        // Length prefixed, so parts can't run into each other
+        for byte in (part.len() as u64).to_le_bytes().iter().chain(*part) {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+/// Write a file, unless it already has these contents
+fn write_if_changed(path: &Path, contents: &str) -> std::io::Result<()> {
+    if std::fs::read(path).is_ok_and(|old| old == contents.as_bytes()) {
+        return Ok(());
+    }
+    std::fs::write(path, contents)
+}
+
+/// Path from the top-level module to the module of a file, by its path relative to the input
+fn module_path(path: &Path, module_suffix: &str) -> String {
+    let filename = path.file_name().unwrap().to_str().unwrap();
+    let filename = filename.split_once('.').unwrap().0;
+    path.parent()
+        .into_iter()
+        .flat_map(Path::iter)
+        .map(|dir| dir.to_str().unwrap())
+        .chain([filename])
+        .map(|name| format!("{name}{module_suffix}"))
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+/// Re-exports each binding name of the prelude from its module,
+/// unless it's bound by more than one file
+fn prelude_module(names: BTreeMap<String, Vec<String>>) -> Item {
+    let mut uses: Vec<ItemUse> = vec![];
+    for (name, paths) in names {
+        if let [path] = paths.as_slice() {
+            uses.push(syn::parse_str(&format!("pub use super::{path}::{name};")).unwrap());
         } else {
-            crate_path = parent;
+            warn!(
+                "`{name}` is bound in {} files, leaving it out of the prelude",
+                paths.len()
+            );
         }
     }
-    if !crate_path.join("Cargo.toml").exists() {
-        panic!("Typescript isn't in a crate");
+    parse_quote! {
+        pub mod prelude {
+            #(#uses)*
+        }
+    }
+}
+
+fn is_dts(path: &Path) -> bool {
+    path.to_str().unwrap().ends_with(".d.ts")
+}
+
+/// A `.ts` or `.tsx` source, unless there's a `.d.ts` for it next to it
+fn is_ts_source(path: &Path) -> bool {
+    let path = path.to_str().unwrap();
+    match path
+        .strip_suffix(".ts")
+        .or_else(|| path.strip_suffix(".tsx"))
+    {
+        Some(stem) => !is_dts(Path::new(path)) && !Path::new(&format!("{stem}.d.ts")).exists(),
+        None => false,
+    }
+}
+
+fn main() -> std::io::Result<ExitCode> {
+    let Args {
+        typescript_path,
+        rust_destination,
+        thread_local_globals,
+        assert_js_cast,
+        catch_throws,
+        final_methods,
+        structural_interfaces,
+        flatten_sole_namespace,
+        typed_array_slices,
+        vec_returns,
+        note_fallbacks,
+        strip_internal,
+        js_module,
+        wasm_bindgen_path,
+        target,
+        dry_run,
+        root_module,
+        single_file,
+        exclude,
+        ts_sources,
+        crate_root,
+        force,
+        stats,
+        prelude,
+        verbose,
+    } = Args::parse();
+    env_logger::Builder::new()
+        .filter_level(match verbose {
+            0 => LevelFilter::Error,
+            1 => LevelFilter::Warn,
+            2 => LevelFilter::Info,
+            3 => LevelFilter::Debug,
+            _ => LevelFilter::Trace,
+        })
+        .format_target(false)
+        .parse_default_env()
+        .init();
+
+    let from_stdin = typescript_path == Path::new("-");
+    let rust_destination = match rust_destination {
+        Some(rust_destination) => rust_destination,
+        None if from_stdin => PathBuf::new(),
+        None => Args::command()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                "RUST_DESTINATION is required unless converting from stdin",
+            )
+            .exit(),
+    };
+
+    // Where the config file is read from
+    let crate_root = crate_root
+        .or_else(|| {
+            typescript_path
+                .ancestors()
+                .find(|dir| dir.join("Cargo.toml").exists())
+                .map(Path::to_path_buf)
+        })
+        .unwrap_or_else(|| {
+            if single_file {
+                rust_destination
+                    .parent()
+                    .unwrap_or(Path::new(""))
+                    .to_path_buf()
+            } else {
+                rust_destination.clone()
+            }
+        });
+
+    let mut config = Config {
+        thread_local_globals,
+        assert_js_cast,
+        catch_throws,
+        final_methods,
+        structural_interfaces,
+        flatten_sole_namespace,
+        typed_array_slices,
+        vec_returns,
+        note_fallbacks,
+        strip_internal,
+        js_module,
+        // Checked by `parse_path`
+        wasm_bindgen_path: syn::parse_str(&wasm_bindgen_path).unwrap(),
+        target,
+        ..Default::default()
+    };
+    config.load_file(&crate_root)?;
+    // Anything that changes how files are converted
+    let options = format!("{} {config:?}", env!("CARGO_PKG_VERSION"));
+    let module_suffix = config.module_suffix.clone();
+    set_config(config);
+
+    if from_stdin {
+        let mut src = String::new();
+        std::io::stdin().read_to_string(&mut src)?;
+        convert_dts_to_writer(&src, &mut std::io::stdout().lock())?;
+        if stats {
+            eprint!("{}", take_stats());
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if !dry_run {
+        if !single_file {
+            std::fs::create_dir_all(&rust_destination)?;
+        } else if let Some(parent) = rust_destination.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
     }
 
     let mut dir_mods: HashMap<PathBuf, HashSet<String>> = HashMap::new();
+    let mut mod_tree = ModTree::default();
+    let mut failed = false;
+    let manifest_path = rust_destination.join(MANIFEST_FILE);
+    let old_manifest = if force || dry_run || single_file {
+        HashMap::new()
+    } else {
+        read_manifest(&manifest_path)
+    };
+    let mut manifest = BTreeMap::new();
+    // Files that the modules of the directory with the same name are appended to
+    let mut named_parents: HashMap<PathBuf, String> = HashMap::new();
+    // Modules of the bindings for the prelude, by name
+    let mut prelude_names: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut add_to_prelude = |path: &Path, file: &syn::File| {
+        let module = module_path(path.strip_prefix(&typescript_path).unwrap(), &module_suffix);
+        for name in binding_names(file) {
+            prelude_names
+                .entry(name.to_string())
+                .or_default()
+                .push(module.clone());
+        }
+    };
+    // Files exporting each enum, since wasm_bindgen can only export one enum by a name
+    let mut enum_files: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    let mut add_enums = |path: &Path, file: &syn::File| {
+        for name in exported_enum_names(file) {
+            enum_files.entry(name).or_default().push(path.to_path_buf());
+        }
+    };
+
+    let mut excluded = GlobSetBuilder::new();
+    exclude.into_iter().for_each(|glob| {
+        excluded.add(glob);
+    });
+    // Each glob was checked by clap, but together they can still be too big to compile
+    let excluded = excluded.build().unwrap_or_else(|e| {
+        Args::command()
+            .error(
+                ErrorKind::InvalidValue,
+                format!("invalid exclude globs: {e}"),
+            )
+            .exit()
+    });
 
-    for entry in WalkDir::new(&typescript_path) {
+    // Excluded directories aren't walked, so they don't get a module either
+    let walker = WalkDir::new(&typescript_path)
+        .into_iter()
+        .filter_entry(|entry| {
+            let path = entry.path().strip_prefix(&typescript_path).unwrap();
+            path.as_os_str().is_empty() || !excluded.is_match(path)
+        });
+    for entry in walker {
         let entry = entry.unwrap();
 
         let mut new_path =
@@ -52,13 +418,19 @@ fn main() -> std::io::Result<()> {
         if new_path == rust_destination {
             continue;
         } else if entry.file_type().is_dir() {
-            std::fs::create_dir_all(&new_path)?;
+            if !dry_run && !single_file {
+                std::fs::create_dir_all(&new_path)?;
+            }
             dir_mods
                 .entry(new_path.parent().unwrap().join("mod.rs"))
                 .or_default()
                 .insert(entry.file_name().to_str().unwrap().to_string());
-        } else if entry.path().to_str().unwrap().ends_with(".d.ts") {
-            println!("{}", entry.path().display());
+        } else if entry.path().to_str().unwrap().ends_with(".d.tsx") {
+            warn!(
+                "{}: declaration files can't contain JSX, skipping",
+                entry.path().display()
+            );
+        } else if is_dts(entry.path()) || (ts_sources && is_ts_source(entry.path())) {
             new_path.pop();
             let filename = entry
                 .file_name()
@@ -72,95 +444,139 @@ fn main() -> std::io::Result<()> {
                 .or_default()
                 .insert(filename.to_string());
             new_path.push(format!("{filename}.rs",));
-            let mut f = File::create(&new_path).unwrap();
-
-            let cm: Lrc<SourceMap> = Default::default();
-            let handler =
-                Handler::with_tty_emitter(ColorConfig::Auto, true, false, Some(cm.clone()));
-
-            let fm = cm.load_file(entry.path())?;
-            let lexer = Lexer::new(
-                Syntax::Typescript(TsConfig {
-                    dts: true,
-                    ..Default::default()
-                }),
-                Default::default(),
-                StringInput::from(&*fm),
-                None,
-            );
-
-            let mut parser = Parser::new_from(lexer);
 
-            for e in parser.take_errors() {
-                e.into_diagnostic(&handler).emit();
+            let relative_path = new_path
+                .strip_prefix(&rust_destination)
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_string();
+            let is_named_parent = entry.path().with_file_name(filename).is_dir();
+            if !dry_run && !single_file {
+                let source = std::fs::read(entry.path())?;
+                // Referenced files are bound too, i.e. re-exported
+                let referenced: Vec<Vec<u8>> = reference_paths(&String::from_utf8_lossy(&source))
+                    .into_iter()
+                    .map(|path| {
+                        std::fs::read(entry.path().with_file_name(path)).unwrap_or_default()
+                    })
+                    .collect();
+                let mut parts = vec![options.as_bytes(), relative_path.as_bytes(), &source];
+                parts.extend(referenced.iter().map(Vec::as_slice));
+                let hash = fnv1a(&parts);
+                manifest.insert(relative_path.clone(), hash);
+                if !is_named_parent
+                    && new_path.exists()
+                    && old_manifest.get(&relative_path) == Some(&hash)
+                {
+                    info!("{} (unchanged)", entry.path().display());
+                    let output = std::fs::read_to_string(&new_path)?;
+                    match syn::parse_file(&output) {
+                        Ok(file) => {
+                            if prelude {
+                                add_to_prelude(entry.path(), &file);
+                            }
+                            add_enums(entry.path(), &file);
+                        }
+                        Err(e) => error!("{}: {e}", new_path.display()),
+                    }
+                    continue;
+                }
+            }
+            if !dry_run {
+                info!("{}", entry.path().display());
             }
 
-            let module = parser
-                .parse_module()
-                .map_err(|e| {
-                    // Unrecoverable fatal error occurred
-                    e.into_diagnostic(&handler).emit()
-                })
-                .expect("failed to parser module");
-
-            let mut file: syn::File = syn::File {
-                shebang: None,
-                attrs: vec![],
-                items: vec![],
+            let converted = if is_dts(entry.path()) {
+                convert_dts_file(entry.path())
+            } else {
+                convert_ts_file(entry.path())
             };
+            let file = match converted {
+                Ok(file) => file,
+                Err(e) if dry_run => {
+                    error!("{e}");
+                    failed = true;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            if prelude && !dry_run {
+                add_to_prelude(entry.path(), &file);
+            }
+            add_enums(entry.path(), &file);
+            if dry_run {
+                println!("{} ({} items)", new_path.display(), binding_count(&file));
+            } else if single_file {
+                mod_tree.insert(entry.path().strip_prefix(&typescript_path).unwrap(), file);
+            } else if is_named_parent {
+                named_parents.insert(new_path, prettyplease::unparse(&file));
+            } else {
+                write_if_changed(&new_path, &prettyplease::unparse(&file))?;
+            }
+        }
+    }
 
-            let uses = imports_to_uses(&module.body);
-            let mut module_items = module_as_binding(&module.body, None);
-
-            let mut cleaner = BindingsCleaner;
-            module_items
-                .iter_mut()
-                .for_each(|i| cleaner.visit_item_mut(i));
-
-            let mut pubs = CollectPubs::default();
-            module_items.iter().for_each(|i| pubs.visit_item(i));
-            uses.iter().for_each(|u| pubs.visit_item_use(u));
+    for (name, mut paths) in enum_files {
+        if paths.len() > 1 {
+            paths.sort();
+            let paths: Vec<_> = paths.iter().map(|p| p.display().to_string()).collect();
+            warn!(
+                "The `{name}` enum is exported by {}, which wasm_bindgen can't link",
+                paths.join(", ")
+            );
+        }
+    }
 
-            // All externed types implement JsObject
-            // so they can be directly sent back to JS.
-            let mut abify = WasmAbify {
-                wasm_abi_types: wasm_abi_set(&pubs.0),
-            };
-            module_items
-                .iter_mut()
-                .for_each(|i| abify.visit_item_mut(i));
-            let mut adder = SysUseAdder {
-                pubs: pubs.0,
-                uses: HashSet::default(),
-            };
-            module_items.iter().for_each(|i| adder.visit_item(i));
+    if stats {
+        eprint!("{}", take_stats());
+    }
 
-            file.items.extend(adder.uses.into_iter().map(Item::Use));
-            file.items.extend(uses.into_iter().map(Item::Use));
-            file.items.append(&mut module_items);
+    if dry_run {
+        return Ok(if failed {
+            ExitCode::FAILURE
+        } else {
+            ExitCode::SUCCESS
+        });
+    }
 
-            write!(f, "{}", prettyplease::unparse(&file))?;
+    if single_file {
+        let mut file = syn::File {
+            shebang: None,
+            attrs: vec![],
+            items: mod_tree.into_items(&module_suffix),
+        };
+        if prelude {
+            file.items.push(prelude_module(prelude_names));
         }
+        let mut f = File::create(&rust_destination)?;
+        write!(f, "{}", prettyplease::unparse(&file))?;
+        return Ok(ExitCode::SUCCESS);
     }
 
+    // Children first, so that their mod.rs exists by the time the parent lists it
+    let mut dir_mods: Vec<_> = dir_mods.into_iter().collect();
+    dir_mods.sort_by_key(|(path, _)| std::cmp::Reverse(path.components().count()));
+
+    let root = rust_destination.join("mod.rs");
+    let mut prelude_names = prelude.then_some(prelude_names);
     for (path, mods) in &dir_mods {
         let named_parent = path.parent().unwrap().with_extension("rs");
-        let named_parent_exists = named_parent.exists();
-        let mut f = if named_parent_exists {
-            OpenOptions::new().append(true).open(&named_parent)?
+        let named_parent_contents = if *path == root {
+            None
         } else {
-            File::create(path)?
+            named_parents.remove(&named_parent)
         };
+        let named_parent_exists = named_parent_contents.is_some();
+        let mut f = named_parent_contents.unwrap_or_default();
+        let exists = |path: &Path| path.exists() || named_parents.contains_key(path);
 
+        let mut mods: Vec<_> = mods.iter().collect();
+        mods.sort();
         for m in mods {
             if named_parent_exists {
-                let name_rs_exists = path
-                    .parent()
-                    .unwrap()
-                    .join(m)
-                    .with_extension("rs")
-                    .exists();
-                let mod_rs_exists = path.parent().unwrap().join(m).join("mod.rs").exists();
+                let name_rs_exists = exists(&path.parent().unwrap().join(m).with_extension("rs"));
+                let mod_rs_exists = exists(&path.parent().unwrap().join(m).join("mod.rs"));
                 if name_rs_exists {
                     writeln!(
                         f,
@@ -171,7 +587,8 @@ fn main() -> std::io::Result<()> {
                             .unwrap()
                             .to_str()
                             .unwrap()
-                    )?;
+                    )
+                    .unwrap();
                 } else if mod_rs_exists {
                     writeln!(
                         f,
@@ -182,29 +599,48 @@ fn main() -> std::io::Result<()> {
                             .unwrap()
                             .to_str()
                             .unwrap()
-                    )?;
+                    )
+                    .unwrap();
                 } else {
                     continue;
                 }
             } else {
-                let name_rs_exists = path
-                    .parent()
-                    .unwrap()
-                    .join(m)
-                    .with_extension("rs")
-                    .exists();
-                let mod_rs_exists = path.parent().unwrap().join(m).join("mod.rs").exists();
+                let name_rs_exists = exists(&path.parent().unwrap().join(m).with_extension("rs"));
+                let mod_rs_exists = exists(&path.parent().unwrap().join(m).join("mod.rs"));
                 if name_rs_exists {
-                    writeln!(f, "#[path = \"{m}.rs\"]")?;
+                    writeln!(f, "#[path = \"{m}.rs\"]").unwrap();
                 } else if mod_rs_exists {
-                    writeln!(f, "#[path = \"{m}/mod.rs\"]")?;
+                    writeln!(f, "#[path = \"{m}/mod.rs\"]").unwrap();
                 } else {
                     continue;
                 }
             }
-            writeln!(f, "#[allow(non_snake_case)]")?;
-            writeln!(f, "pub mod {m}Mod;")?;
+            writeln!(f, "#[allow(non_snake_case)]").unwrap();
+            writeln!(f, "pub mod {m}{module_suffix};").unwrap();
+        }
+
+        if *path == root {
+            if let Some(names) = prelude_names.take() {
+                f.push_str(&prettyplease::unparse(&syn::File {
+                    shebang: None,
+                    attrs: vec![],
+                    items: vec![prelude_module(names)],
+                }));
+            }
+        }
+
+        if named_parent_exists {
+            write_if_changed(&named_parent, &f)?;
+        } else if *path == root {
+            write_if_changed(&rust_destination.join(&root_module), &f)?;
+        } else {
+            write_if_changed(path, &f)?;
         }
     }
-    Ok(())
+    // Named parents of directories without any bindings
+    for (path, contents) in named_parents {
+        write_if_changed(&path, &contents)?;
+    }
+    write_manifest(&manifest_path, &manifest)?;
+    Ok(ExitCode::SUCCESS)
 }
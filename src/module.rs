@@ -1,23 +1,34 @@
 use std::collections::HashMap;
+use std::path::Path;
 
+use log::warn;
+use swc_common::Spanned;
 use swc_ecma_ast::{
     Decl, ExportDecl, ExportDefaultExpr, ExportDefaultSpecifier, ExportNamedSpecifier,
-    ExportSpecifier, Ident, ImportDecl, ImportDefaultSpecifier, ImportNamedSpecifier,
-    ImportSpecifier, ModuleDecl, ModuleExportName, ModuleItem, NamedExport, Stmt,
-    TsNamespaceExportDecl,
+    ExportNamespaceSpecifier, ExportSpecifier, Ident, ImportDecl, ImportDefaultSpecifier,
+    ImportNamedSpecifier, ImportSpecifier, ImportStarAsSpecifier, ModuleDecl, ModuleExportName,
+    ModuleItem, NamedExport, Stmt, TsExportAssignment, TsInterfaceDecl, TsModuleBlock,
+    TsModuleName, TsNamespaceBody, TsNamespaceExportDecl,
 };
 use syn::{
     parse_quote,
     punctuated::Punctuated,
     token::{Brace, Comma},
     visit_mut::VisitMut,
-    Expr, ExprArray, ExprAssign, ForeignItem, Item, ItemForeignMod, ItemUse, Token, UseGroup,
-    UsePath, UseTree,
+    Expr, ExprArray, ExprAssign, ForeignItem, Item, ItemForeignMod, ItemMod, ItemUse, Token,
+    UseGroup, UseName, UsePath, UseRename, UseTree,
 };
 
 use crate::{
-    decl::{decl_ident, decl_to_items, ts_module_to_binding},
-    util::{import_prefix_to_idents, sanitize_sym, ModuleBindingsCleaner},
+    config::config,
+    decl::{
+        decl_ident, decl_to_items, js_module_raw_name, ts_enum_to_binding, ts_module_to_binding,
+    },
+    jsdoc::{is_internal, reference_directives, source_path},
+    notes::attach_notes,
+    parse_dts_file,
+    util::{import_prefix_to_idents, module_name, sanitize_sym, ModuleBindingsCleaner},
+    wasm::{wasm_bindgen_args, wasm_bindgen_attr, wasm_bindgen_path},
 };
 
 pub fn imports_to_uses(body: &[ModuleItem]) -> Vec<ItemUse> {
@@ -36,15 +47,15 @@ pub fn imports_to_uses(body: &[ModuleItem]) -> Vec<ItemUse> {
                             imported,
                             ..
                         }) => {
-                            let name = sanitize_sym(sym);
+                            let rename = sanitize_sym(sym);
                             if let Some(imported) = imported {
-                                let rename = sanitize_sym(match imported {
+                                let name = sanitize_sym(match imported {
                                     ModuleExportName::Ident(Ident { sym, .. }) => sym,
                                     ModuleExportName::Str(s) => &s.value,
                                 });
                                 leaves.push(parse_quote!(#name as #rename));
                             } else {
-                                leaves.push(parse_quote!(#name));
+                                leaves.push(parse_quote!(#rename));
                             }
                         }
                         ImportSpecifier::Default(ImportDefaultSpecifier {
@@ -80,6 +91,28 @@ pub fn imports_to_uses(body: &[ModuleItem]) -> Vec<ItemUse> {
                 ..
             })) => {
                 let name = sanitize_sym(&expr.as_ident().unwrap().sym);
+                // `default` is only a keyword in impls, so it's a fine name for the export
+                uses.push(parse_quote! {
+                    pub use self::#name as default;
+                });
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::TsExportAssignment(TsExportAssignment {
+                expr,
+                ..
+            })) => {
+                let raw_name: &str = match expr.as_ident() {
+                    Some(Ident { sym, .. }) => sym,
+                    None => {
+                        warn!("Only identifiers can be export assigned");
+                        continue;
+                    }
+                };
+                // Namespaces are emitted as modules
+                let name = if declares_namespace(body, raw_name) {
+                    sanitize_sym(&module_name(raw_name))
+                } else {
+                    sanitize_sym(raw_name)
+                };
                 uses.push(parse_quote! {
                     pub use self::#name as default;
                 });
@@ -99,6 +132,22 @@ pub fn imports_to_uses(body: &[ModuleItem]) -> Vec<ItemUse> {
                     };
                     vec![fake_use]
                 };
+                // i.e. `export * as ns from "./a"`, which re-exports the module itself
+                if let [ExportSpecifier::Namespace(ExportNamespaceSpecifier { name, .. })] =
+                    &specifiers[..]
+                {
+                    let mut prefix = prefix;
+                    let module = prefix.pop().unwrap();
+                    let rename = sanitize_sym(match name {
+                        ModuleExportName::Ident(Ident { sym, .. }) => sym,
+                        ModuleExportName::Str(s) => &s.value,
+                    });
+                    let use_tree = use_path_to_use_tree(prefix, parse_quote!(#module as #rename));
+                    uses.push(parse_quote! {
+                        pub use #use_tree;
+                    });
+                    continue;
+                }
                 let mut leaves: Punctuated<UseTree, Comma> = Punctuated::new();
                 for spec in specifiers {
                     match spec {
@@ -124,8 +173,9 @@ pub fn imports_to_uses(body: &[ModuleItem]) -> Vec<ItemUse> {
                             let rename = sanitize_sym(sym);
                             leaves.push(parse_quote!(default as #rename));
                         }
-                        ExportSpecifier::Namespace(n) => {
-                            todo!("{n:?}")
+                        // Only valid on its own
+                        ExportSpecifier::Namespace(_) => {
+                            warn!("Namespace exports can't be combined with other exports");
                         }
                     }
                 }
@@ -145,6 +195,199 @@ pub fn imports_to_uses(body: &[ModuleItem]) -> Vec<ItemUse> {
             _ => {}
         }
     }
+
+    let mut renamer = RenameCollidingUses::default();
+    uses.iter_mut().for_each(|u| renamer.visit_item_use_mut(u));
+    uses
+}
+
+/// Renames uses that would bring in a name that's already been used,
+/// i.e. `export { Foo } from "./a"; export { Foo } from "./b";`
+#[derive(Default)]
+struct RenameCollidingUses {
+    /// How many times each name has been used
+    seen: HashMap<String, usize>,
+}
+
+impl VisitMut for RenameCollidingUses {
+    fn visit_use_tree_mut(&mut self, tree: &mut UseTree) {
+        let (name, rename) = match tree {
+            UseTree::Name(UseName { ident }) if ident != "self" => (ident.clone(), ident.clone()),
+            UseTree::Rename(UseRename { ident, rename, .. }) => (ident.clone(), rename.clone()),
+            _ => return syn::visit_mut::visit_use_tree_mut(self, tree),
+        };
+        let count = self.seen.entry(rename.to_string()).or_default();
+        *count += 1;
+        if *count > 1 {
+            let renamed = sanitize_sym(&format!(
+                "{}{count}",
+                rename.to_string().trim_start_matches("r#")
+            ));
+            warn!("`{rename}` is already used, renaming it to `{renamed}`");
+            *tree = parse_quote!(#name as #renamed);
+        }
+    }
+}
+
+/// Whether there's a namespace with this name at the top level of the module
+fn declares_namespace(body: &[ModuleItem], raw_name: &str) -> bool {
+    body.iter().any(|item| match item {
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            decl: Decl::TsModule(tsm),
+            ..
+        }))
+        | ModuleItem::Stmt(Stmt::Decl(Decl::TsModule(tsm))) => {
+            matches!(&tsm.id, TsModuleName::Ident(Ident { sym, .. }) if sym == raw_name)
+        }
+        _ => false,
+    })
+}
+
+/// The name & body of the namespace a module declares, if it declares nothing else
+pub fn sole_namespace(body: &[ModuleItem]) -> Option<(&str, &[ModuleItem])> {
+    let mut decls = body
+        .iter()
+        .filter(|item| !matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(_))));
+    let tsm = match (decls.next(), decls.next()) {
+        (
+            Some(
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                    decl: Decl::TsModule(tsm),
+                    ..
+                }))
+                | ModuleItem::Stmt(Stmt::Decl(Decl::TsModule(tsm))),
+            ),
+            None,
+        ) if !tsm.global => tsm,
+        _ => return None,
+    };
+    match (&tsm.id, tsm.body.as_ref()) {
+        (
+            TsModuleName::Ident(Ident { sym, .. }),
+            Some(TsNamespaceBody::TsModuleBlock(TsModuleBlock { body, .. })),
+        ) => Some((sym, body)),
+        _ => None,
+    }
+}
+
+/// Converts the contents of a namespace to bindings at the root of the module
+pub fn sole_namespace_as_binding(raw_name: &str, body: &[ModuleItem]) -> Vec<Item> {
+    let mut items = module_as_binding(body, Scope::Namespace(raw_name));
+    let use_super: Item = parse_quote!(
+        use super::*;
+    );
+    for item in &mut items {
+        if *item == use_super {
+            let wasm_bindgen = wasm_bindgen_path();
+            *item = parse_quote! {
+                use #wasm_bindgen::prelude::wasm_bindgen;
+            };
+        }
+    }
+    items
+}
+
+/// Whether a file is a script rather than a module, since it has no imports or exports,
+/// so everything it declares is global
+pub fn is_script(body: &[ModuleItem]) -> bool {
+    body.iter().all(|item| matches!(item, ModuleItem::Stmt(_)))
+}
+
+/// The name a declaration is bound by in its file's module, unless it's bound elsewhere
+fn binding_name(decl: &Decl) -> Option<syn::Ident> {
+    match (decl, decl_ident(decl)) {
+        // Bound to their JS module, not the file
+        (Decl::TsModule(tsm), _) if matches!(tsm.id, TsModuleName::Str(_)) => None,
+        (Decl::TsModule(_), Some(ident)) => Some(sanitize_sym(&module_name(ident))),
+        (_, ident) => ident.map(sanitize_sym),
+    }
+}
+
+/// `pub use`s for the exports of files referenced with `/// <reference path="..." />`,
+/// or all the declarations of ones that are scripts, leaving out names declared in `body`
+pub fn references_to_uses(body: &[ModuleItem]) -> Vec<ItemUse> {
+    let mut local_names: Vec<syn::Ident> = vec![];
+    for item in body {
+        match item {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { decl, .. }))
+            | ModuleItem::Stmt(Stmt::Decl(decl)) => local_names.extend(binding_name(decl)),
+            ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl { specifiers, .. })) => {
+                for spec in specifiers {
+                    let (ImportSpecifier::Named(ImportNamedSpecifier { local, .. })
+                    | ImportSpecifier::Default(ImportDefaultSpecifier { local, .. })
+                    | ImportSpecifier::Namespace(ImportStarAsSpecifier { local, .. })) = spec;
+                    local_names.push(sanitize_sym(&local.sym));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut uses = vec![];
+    for (kind, path) in reference_directives() {
+        if kind != "path" {
+            continue;
+        }
+        let Some(dir) = source_path().and_then(|src| src.parent().map(Path::to_path_buf)) else {
+            warn!("Can't resolve the reference to {path} without a source file");
+            continue;
+        };
+        let referenced = match parse_dts_file(&dir.join(&path)) {
+            Ok(referenced) => referenced,
+            Err(e) => {
+                warn!("Can't read the reference to {path}: {e}");
+                continue;
+            }
+        };
+
+        let script = is_script(&referenced.body);
+        let mut names: Vec<syn::Ident> = vec![];
+        for item in &referenced.body {
+            let decl = match item {
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { decl, .. })) => decl,
+                ModuleItem::Stmt(Stmt::Decl(decl)) if script => decl,
+                _ => continue,
+            };
+            let Some(name) = binding_name(decl) else {
+                continue;
+            };
+            // i.e. a class & interface with the same name, or one declared here too
+            if !names.contains(&name) && !local_names.contains(&name) {
+                names.push(name);
+            }
+        }
+        if names.is_empty() {
+            continue;
+        }
+
+        // Paths are relative even without a leading `./`
+        let path = path
+            .strip_suffix(".d.ts")
+            .or_else(|| path.strip_suffix(".ts"))
+            .unwrap_or(&path);
+        let path = if path.starts_with('.') {
+            path.to_string()
+        } else {
+            format!("./{path}")
+        };
+        let prefix = import_prefix_to_idents(&path);
+        let leaf = if names.len() > 1 {
+            UseTree::Group(UseGroup {
+                brace_token: Brace::default(),
+                items: names
+                    .into_iter()
+                    .map::<UseTree, _>(|name| parse_quote!(#name))
+                    .collect(),
+            })
+        } else {
+            let name = &names[0];
+            parse_quote!(#name)
+        };
+        let use_tree = use_path_to_use_tree(prefix, leaf);
+        uses.push(parse_quote! {
+            pub use #use_tree;
+        });
+    }
     uses
 }
 
@@ -160,21 +403,54 @@ fn use_path_to_use_tree(mut prefix: Vec<syn::Ident>, leaf: UseTree) -> UseTree {
     tree
 }
 
+/// What the body of a module is declared by
+#[derive(Clone, Copy, Debug)]
+pub enum Scope<'a> {
+    /// A declaration file
+    File,
+    /// `declare namespace X {}`
+    Namespace(&'a str),
+    /// `declare module "x" {}`, bound to that JS module
+    Module(&'a str),
+}
+
 /// Converts a JS module to an extern binding
 ///
-/// If this is a namespace, assume everything inside it is exported.
-pub fn module_as_binding(body: &[ModuleItem], namespace: Option<&str>) -> Vec<Item> {
+/// If this is a namespace or ambient module, assume everything inside it is exported.
+pub fn module_as_binding(body: &[ModuleItem], scope: Scope) -> Vec<Item> {
     let mut items = vec![];
 
     let mut enclosing_ns: Option<&str> = None;
     let mut foreign_items = vec![];
+    // Where the extern block goes, so the output follows the source as much as it can
+    let mut extern_position = None;
     let mut default_ident = None;
-    let mut declared_bodies: HashMap<String, &Decl> = HashMap::new();
+    let mut declared_bodies: HashMap<String, Vec<&Decl>> = HashMap::new();
+    let mut merged_interfaces = merge_interfaces(body);
+    // Specifiers of the `declare module "..."` blocks by the name of their module
+    let mut js_modules: HashMap<syn::Ident, String> = HashMap::new();
+    let script = is_script(body);
     for item in body {
+        if is_internal(item.span_lo()) {
+            continue;
+        }
         match item {
-            ModuleItem::Stmt(Stmt::Decl(decl)) if namespace.is_none() => {
+            ModuleItem::Stmt(Stmt::Decl(Decl::TsModule(tsm)))
+                if matches!(tsm.id, TsModuleName::Str(_)) =>
+            {
+                if let (TsModuleName::Str(specifier), Some(Item::Mod(module))) =
+                    (&tsm.id, ts_module_to_binding(tsm))
+                {
+                    add_js_module(&mut items, &mut js_modules, &specifier.value, module);
+                }
+            }
+            // Only exports are bound, unless the file is a script declaring globals
+            ModuleItem::Stmt(Stmt::Decl(decl)) if matches!(scope, Scope::File) && !script => {
                 if let Some(ident) = decl_ident(decl) {
-                    declared_bodies.insert(ident.to_string(), decl);
+                    declared_bodies
+                        .entry(ident.to_string())
+                        .or_default()
+                        .push(decl);
                 }
             }
             ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
@@ -183,17 +459,46 @@ pub fn module_as_binding(body: &[ModuleItem], namespace: Option<&str>) -> Vec<It
             }))
             | ModuleItem::Stmt(Stmt::Decl(Decl::TsModule(tsm))) => {
                 let mod_extern = ts_module_to_binding(tsm);
-                items.extend(mod_extern.into_iter());
+                items.extend(mod_extern);
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                decl: Decl::TsEnum(tse),
+                ..
+            }))
+            | ModuleItem::Stmt(Stmt::Decl(Decl::TsEnum(tse))) => {
+                items.append(&mut ts_enum_to_binding(tse));
             }
             ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { decl, .. }))
             | ModuleItem::Stmt(Stmt::Decl(decl)) => {
+                let merged;
+                let decl = match decl {
+                    Decl::TsInterface(iface) => match merged_interfaces.get_mut(&*iface.id.sym) {
+                        // Emitted at the first declaration
+                        Some(None) => continue,
+                        Some(first) => {
+                            merged = first.take().unwrap();
+                            &merged
+                        }
+                        None => decl,
+                    },
+                    _ => decl,
+                };
                 let mut decl_foreign_items = decl_to_items(decl);
+                attach_notes(&mut decl_foreign_items);
+                if !decl_foreign_items.is_empty() {
+                    extern_position.get_or_insert(items.len());
+                }
                 foreign_items.append(&mut decl_foreign_items);
             }
-            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export_default))
-                if namespace.is_none() =>
-            {
-                default_ident = export_default.expr.as_ident().map(|i| i.sym.to_string());
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(ExportDefaultExpr {
+                expr,
+                ..
+            }))
+            | ModuleItem::ModuleDecl(ModuleDecl::TsExportAssignment(TsExportAssignment {
+                expr,
+                ..
+            })) if matches!(scope, Scope::File) => {
+                default_ident = expr.as_ident().map(|i| i.sym.to_string());
                 continue;
             }
             ModuleItem::ModuleDecl(ModuleDecl::TsNamespaceExport(TsNamespaceExportDecl {
@@ -201,7 +506,7 @@ pub fn module_as_binding(body: &[ModuleItem], namespace: Option<&str>) -> Vec<It
                 ..
             })) => enclosing_ns = Some(sym),
             ModuleItem::Stmt(_) => {
-                eprintln!("Didn't expect non decl statement");
+                warn!("Didn't expect non decl statement");
             }
             ModuleItem::ModuleDecl(
                 ModuleDecl::ExportNamed(_)
@@ -215,30 +520,30 @@ pub fn module_as_binding(body: &[ModuleItem], namespace: Option<&str>) -> Vec<It
         }
     }
 
-    if let Some(decl) = default_ident.as_ref().and_then(|i| declared_bodies.get(i)) {
-        let mut decl_foreign_items = decl_to_items(decl);
-        foreign_items.append(&mut decl_foreign_items);
+    // A namespace may share its name with a function or class, i.e. a callable namespace
+    let default_decls = default_ident.as_ref().and_then(|i| declared_bodies.get(i));
+    for decl in default_decls.into_iter().flatten() {
+        match decl {
+            Decl::TsModule(tsm) => items.extend(ts_module_to_binding(tsm)),
+            Decl::TsEnum(tse) => items.append(&mut ts_enum_to_binding(tse)),
+            decl => {
+                let mut decl_foreign_items = decl_to_items(decl);
+                attach_notes(&mut decl_foreign_items);
+                if !decl_foreign_items.is_empty() {
+                    extern_position.get_or_insert(items.len());
+                }
+                foreign_items.append(&mut decl_foreign_items);
+            }
+        }
     }
 
-    let mut dedupe = ModuleBindingsCleaner::default();
-    foreign_items
-        .iter_mut()
-        .for_each(|i| dedupe.visit_foreign_item_mut(i));
+    ModuleBindingsCleaner::default().clean(&mut foreign_items);
 
     if !foreign_items.is_empty() {
-        if namespace.is_some() {
-            items.push(parse_quote! {
-                use super::*;
-            });
-        } else {
-            items.push(parse_quote! {
-                use wasm_bindgen::prelude::wasm_bindgen;
-            });
-        }
-
-        items.push(
+        items.insert(
+            extern_position.unwrap_or(items.len()),
             ItemForeignMod {
-                attrs: vec![parse_quote!(#[wasm_bindgen])],
+                attrs: vec![wasm_bindgen_attr()],
                 abi: parse_quote!(extern "C"),
                 brace_token: Brace::default(),
                 items: foreign_items,
@@ -247,14 +552,142 @@ pub fn module_as_binding(body: &[ModuleItem], namespace: Option<&str>) -> Vec<It
         );
     }
 
-    if let Some(ns) = namespace.or(enclosing_ns) {
+    // Namespace modules `use super::*` so they need this too
+    if !items.is_empty() {
+        if let Scope::File = scope {
+            let wasm_bindgen = wasm_bindgen_path();
+            items.insert(
+                0,
+                parse_quote! {
+                    use #wasm_bindgen::prelude::wasm_bindgen;
+                },
+            );
+        } else {
+            items.insert(
+                0,
+                parse_quote! {
+                    use super::*;
+                },
+            );
+        }
+    }
+
+    let namespace = match scope {
+        Scope::Namespace(ns) => Some(ns),
+        Scope::File | Scope::Module(_) => None,
+    };
+    // A UMD global (`export as namespace X`) is only where the exports are without a module
+    let umd_global = enclosing_ns.filter(|_| config(|c| c.js_module.is_none()));
+    if let Some(ns) = namespace.or(umd_global) {
         let mut ans = ApplyNamespace(ns.to_string());
         items.iter_mut().for_each(|i| ans.visit_item_mut(i));
     }
+    if let Scope::Module(specifier) = scope {
+        apply_js_module(&mut items, specifier);
+    }
 
     items
 }
 
+/// Add the module for a `declare module "..."` block, merging blocks for the same specifier &
+/// renaming ones for specifiers that only have the same name, i.e. `"pkg/sub"` & `"pkg-sub"`
+fn add_js_module(
+    items: &mut Vec<Item>,
+    js_modules: &mut HashMap<syn::Ident, String>,
+    specifier: &str,
+    mut module: ItemMod,
+) {
+    let name = module.ident.clone();
+    let mut n = 0;
+    while let Some(existing) = js_modules.get(&module.ident) {
+        if existing == specifier {
+            let Some(Item::Mod(existing)) = items
+                .iter_mut()
+                .find(|item| matches!(item, Item::Mod(m) if m.ident == module.ident))
+            else {
+                unreachable!("{} was added", module.ident);
+            };
+            let use_super: Item = parse_quote!(
+                use super::*;
+            );
+            let (_, merged) = existing.content.as_mut().unwrap();
+            let (_, content) = module.content.unwrap();
+            merged.extend(content.into_iter().filter(|item| *item != use_super));
+            return;
+        }
+        n += 1;
+        module.ident = sanitize_sym(&module_name(&format!(
+            "{}_{n}",
+            js_module_raw_name(specifier)
+        )));
+    }
+    if n > 0 {
+        warn!(
+            "Modules \"{}\" & \"{specifier}\" are both named {name}, binding the latter as {}",
+            js_modules[&name], module.ident
+        );
+    }
+    js_modules.insert(module.ident.clone(), specifier.to_string());
+    items.push(module.into());
+}
+
+/// Interfaces declared more than once, with their members merged into one declaration
+fn merge_interfaces(body: &[ModuleItem]) -> HashMap<String, Option<Decl>> {
+    let mut interfaces: HashMap<String, Vec<&TsInterfaceDecl>> = HashMap::new();
+    for item in body {
+        if let ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            decl: Decl::TsInterface(iface),
+            ..
+        }))
+        | ModuleItem::Stmt(Stmt::Decl(Decl::TsInterface(iface))) = item
+        {
+            interfaces
+                .entry(iface.id.sym.to_string())
+                .or_default()
+                .push(iface);
+        }
+    }
+
+    interfaces
+        .into_iter()
+        .filter(|(_, decls)| decls.len() > 1)
+        .map(|(name, decls)| {
+            let mut merged = decls[0].clone();
+            for iface in &decls[1..] {
+                merged.extends.extend(iface.extends.iter().cloned());
+                merged.body.body.extend(iface.body.body.iter().cloned());
+            }
+            (name, Some(Decl::TsInterface(Box::new(merged))))
+        })
+        .collect()
+}
+
+/// Import the extern blocks from a JS module rather than the global scope,
+/// unless they're already imported from one
+pub fn apply_js_module(items: &mut [Item], specifier: &str) {
+    let mut ajm = ApplyJsModule(specifier.to_string());
+    items.iter_mut().for_each(|i| ajm.visit_item_mut(i));
+}
+
+struct ApplyJsModule(String);
+
+impl VisitMut for ApplyJsModule {
+    fn visit_item_foreign_mod_mut(&mut self, ifm: &mut ItemForeignMod) {
+        let specifier = &self.0;
+        let mut args: Punctuated<Expr, Comma> = wasm_bindgen_args(&ifm.attrs).into_iter().collect();
+        let has_module = args.iter().any(
+            |arg| matches!(arg, Expr::Assign(ExprAssign { left, .. }) if **left == parse_quote!(module)),
+        );
+        if has_module {
+            return;
+        }
+        args.push(parse_quote!(module = #specifier));
+        ifm.attrs
+            .retain(|attr| attr.path.get_ident() != Some(&parse_quote!(wasm_bindgen)));
+        ifm.attrs.push(parse_quote!(#[wasm_bindgen(#args)]));
+    }
+}
+
 struct ApplyNamespace(String);
 
 impl VisitMut for ApplyNamespace {
@@ -0,0 +1,43 @@
+//! Notes about lossy conversions, attached as docs to the generated item
+
+use std::cell::RefCell;
+
+use syn::{parse_quote, Attribute, ForeignItem};
+
+thread_local! {
+    static NOTES: RefCell<Vec<String>> = RefCell::default();
+}
+
+/// Record a note for the item currently being generated, unless it already has it
+pub fn note(note: String) {
+    NOTES.with(|n| {
+        let mut notes = n.borrow_mut();
+        if !notes.contains(&note) {
+            notes.push(note);
+        }
+    });
+}
+
+/// Note for a type bound as `JsValue`, given as TS source or the Rust type it would've been
+pub fn fallback_note(ty: &str) -> String {
+    format!("`{ty}` is bound as `JsValue`")
+}
+
+/// Attach pending notes as docs to the first of the items generated for a declaration
+pub fn attach_notes(items: &mut [ForeignItem]) {
+    let notes = NOTES.with(|n| std::mem::take(&mut *n.borrow_mut()));
+    let attrs = match items.first_mut() {
+        Some(ForeignItem::Fn(f)) => &mut f.attrs,
+        Some(ForeignItem::Static(s)) => &mut s.attrs,
+        Some(ForeignItem::Type(t)) => &mut t.attrs,
+        _ => return,
+    };
+    for note in notes {
+        let note = format!(" {note}");
+        let doc: Attribute = parse_quote!(#[doc = #note]);
+        // i.e. when both the TS type & the Rust type it was bound as fell back
+        if !attrs.contains(&doc) {
+            attrs.push(doc);
+        }
+    }
+}
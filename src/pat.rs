@@ -1,12 +1,64 @@
-use swc_ecma_ast::{BindingIdent, Ident, Pat, RestPat};
-use syn::{parse_quote, PatType, Token};
+use swc_ecma_ast::{ArrayPat, AssignPat, BindingIdent, Ident, ObjectPat, Pat, RestPat};
+use syn::{
+    parse_quote, parse_str, punctuated::Punctuated, token::Comma, visit_mut::VisitMut, FnArg,
+    PatType, Token,
+};
 
-use crate::{ty::ts_type_to_type, util::sanitize_sym, wasm::js_value};
+use crate::{
+    jsdoc::{jsdoc, jsdoc_value_integer, NumberToInteger},
+    ty::{ts_type_to_type, ClosuresToFunctions},
+    util::sanitize_sym,
+    wasm::js_value,
+};
+
+/// The params of a signature, with destructured ones named by position (i.e. `arg1`)
+///
+/// Those names stay clear of the names of the other params.
+pub fn params_to_fn_args<'a>(pats: impl IntoIterator<Item = &'a Pat>) -> Punctuated<FnArg, Comma> {
+    let pats: Vec<&Pat> = pats.into_iter().collect();
+    let names: Vec<String> = pats
+        .iter()
+        .filter_map(|pat| binding_ident(pat))
+        .map(|ident| sanitize_sym(&ident.sym).to_string())
+        .collect();
+
+    let mut params = Punctuated::new();
+    for (i, pat) in pats.into_iter().enumerate() {
+        let mut pat_type = pat_to_pat_type(pat);
+        if binding_ident(pat).is_none() {
+            let mut name = format!("arg{i}");
+            while names.contains(&name) {
+                name.push('_');
+            }
+            let ident: syn::Ident = parse_str(&name).unwrap();
+            *pat_type.pat = parse_quote!(#ident);
+        }
+        ClosuresToFunctions.visit_param_mut(&mut pat_type.ty);
+        params.push(FnArg::Typed(pat_type));
+    }
+    params
+}
+
+/// The name of a param, unless it's destructured
+fn binding_ident(pat: &Pat) -> Option<&Ident> {
+    match pat {
+        Pat::Ident(BindingIdent { id, .. }) => Some(id),
+        Pat::Rest(RestPat { arg, .. }) => binding_ident(arg),
+        Pat::Assign(AssignPat { left, .. }) => binding_ident(left),
+        _ => None,
+    }
+}
 
 pub fn pat_to_pat_type(pat: &Pat) -> PatType {
     match pat {
         Pat::Ident(BindingIdent {
-            id: Ident { sym, optional, .. },
+            id:
+                Ident {
+                    span,
+                    sym,
+                    optional,
+                    ..
+                },
             type_ann,
         }) => {
             let pat: syn::Pat = {
@@ -18,6 +70,9 @@ pub fn pat_to_pat_type(pat: &Pat) -> PatType {
             } else {
                 js_value().into()
             };
+            if let Some(int) = jsdoc(span.lo).as_deref().and_then(jsdoc_value_integer) {
+                NumberToInteger(int).visit_type_mut(&mut ty);
+            }
 
             if *optional {
                 ty = parse_quote!(::std::option::Option<#ty>);
@@ -32,7 +87,44 @@ pub fn pat_to_pat_type(pat: &Pat) -> PatType {
         }
         // TODO: wasm bindgen variadic
         Pat::Rest(RestPat { arg, .. }) => pat_to_pat_type(arg),
-        Pat::Array(_) | Pat::Object(_) | Pat::Assign(_) | Pat::Invalid(_) | Pat::Expr(_) => {
+        // Destructured fields aren't visible across the FFI boundary
+        Pat::Object(ObjectPat {
+            type_ann, optional, ..
+        })
+        | Pat::Array(ArrayPat {
+            type_ann, optional, ..
+        }) => {
+            let mut ty = if let Some(ann) = type_ann {
+                ts_type_to_type(&ann.type_ann)
+            } else if pat.is_array() {
+                let js_value = js_value();
+                parse_quote!(::std::boxed::Box<[#js_value]>)
+            } else {
+                js_value().into()
+            };
+
+            if *optional {
+                ty = parse_quote!(::std::option::Option<#ty>);
+            }
+
+            PatType {
+                attrs: vec![],
+                pat: Box::new(parse_quote!(arg0)),
+                colon_token: <Token!(:)>::default(),
+                ty: Box::new(ty),
+            }
+        }
+        // Defaults can't be expressed in bindings, so the param is just optional
+        Pat::Assign(AssignPat { left, type_ann, .. }) => {
+            let mut pat_type = pat_to_pat_type(left);
+            if let Some(ann) = type_ann {
+                *pat_type.ty = ts_type_to_type(&ann.type_ann);
+            }
+            let ty = &pat_type.ty;
+            *pat_type.ty = parse_quote!(::std::option::Option<#ty>);
+            pat_type
+        }
+        Pat::Invalid(_) | Pat::Expr(_) => {
             todo!("{pat:?}")
         }
     }
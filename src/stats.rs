@@ -0,0 +1,106 @@
+//! Counts of what was bound, & what couldn't be bound faithfully
+
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::PathBuf;
+
+use swc_common::Span;
+
+use crate::jsdoc::{line_column, snippet, source_path};
+
+thread_local! {
+    static STATS: RefCell<Stats> = RefCell::default();
+}
+
+/// What was converted, accumulated until [take_stats] is called
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Stats {
+    pub files: usize,
+    pub classes: usize,
+    pub interfaces: usize,
+    pub functions: usize,
+    pub enums: usize,
+    /// Types bound as `JsValue` because they couldn't be expressed in Rust
+    pub fallbacks: BTreeSet<Construct>,
+    /// Declarations & members that were left out
+    pub skipped: BTreeSet<Construct>,
+}
+
+/// A construct in the declarations, i.e. `string | number`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Construct {
+    pub path: Option<PathBuf>,
+    /// 1-based line & column
+    pub line_column: Option<(usize, usize)>,
+    /// Source text, with whitespace collapsed
+    pub source: String,
+}
+
+impl Construct {
+    fn at(span: Span) -> Self {
+        Self {
+            path: source_path(),
+            line_column: line_column(span.lo),
+            source: snippet(span).unwrap_or_default(),
+        }
+    }
+}
+
+impl fmt::Display for Construct {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}", path.display())?,
+            None => write!(f, "<stdin>")?,
+        }
+        if let Some((line, column)) = self.line_column {
+            write!(f, ":{line}:{column}")?;
+        }
+        write!(f, ": {}", self.source)
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} files", self.files)?;
+        writeln!(f, "{} classes", self.classes)?;
+        writeln!(f, "{} interfaces", self.interfaces)?;
+        writeln!(f, "{} functions", self.functions)?;
+        writeln!(f, "{} enums", self.enums)?;
+        writeln!(f, "{} types bound as JsValue", self.fallbacks.len())?;
+        for construct in &self.fallbacks {
+            writeln!(f, "  {construct}")?;
+        }
+        writeln!(f, "{} skipped", self.skipped.len())?;
+        for construct in &self.skipped {
+            writeln!(f, "  {construct}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Update the counts for the file being converted
+pub fn count(f: impl FnOnce(&mut Stats)) {
+    STATS.with(|s| f(&mut s.borrow_mut()))
+}
+
+/// Record a type at `span` that's bound as `JsValue`
+pub fn fallback(span: Span) {
+    let construct = Construct::at(span);
+    count(|s| {
+        s.fallbacks.insert(construct);
+    });
+}
+
+/// Record a declaration or member at `span` that's left out
+pub fn skipped(span: Span) {
+    let construct = Construct::at(span);
+    count(|s| {
+        s.skipped.insert(construct);
+    });
+}
+
+/// The stats of everything converted on this thread since the last call
+pub fn take_stats() -> Stats {
+    STATS.with(|s| std::mem::take(&mut *s.borrow_mut()))
+}
@@ -0,0 +1,282 @@
+//! Declarations in the file being converted, for resolving types that refer to them
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+};
+
+use swc_ecma_ast::{
+    ClassDecl, Decl, ExportDecl, Expr, Ident, ImportDecl, ImportDefaultSpecifier,
+    ImportNamedSpecifier, ImportSpecifier, ImportStarAsSpecifier, Lit, ModuleDecl, ModuleItem,
+    Stmt, Str, TsEntityName, TsInterfaceBody, TsInterfaceDecl, TsModuleName, TsNamespaceBody,
+    TsPropertySignature, TsType, TsTypeAliasDecl, TsTypeElement, TsTypeLit, TsTypeRef,
+};
+
+thread_local! {
+    /// Members of interfaces & object type aliases by name
+    static MEMBERS: RefCell<HashMap<String, Vec<TsTypeElement>>> = RefCell::default();
+    /// Classes extended by another class anywhere in the file
+    static SUPERCLASSES: RefCell<HashSet<String>> = RefCell::default();
+    /// Source of each `import * as X from "..."` by the name it's imported as
+    static NAMESPACE_IMPORTS: RefCell<HashMap<String, String>> = RefCell::default();
+    /// Enums anywhere in the file, qualified by their namespaces (i.e. `ns.Kind`)
+    static ENUMS: RefCell<HashSet<String>> = RefCell::default();
+    /// Classes, interfaces, type aliases, enums & imports anywhere in the file, qualified by their
+    /// namespaces, and the types generated for them so far
+    static TYPES: RefCell<HashSet<String>> = RefCell::default();
+    /// Namespaces enclosing the declarations being converted, outermost first
+    static SCOPE: RefCell<Vec<String>> = RefCell::default();
+}
+
+/// Collect the declarations at the top level of a module
+pub fn collect_symbols(body: &[ModuleItem]) {
+    let mut members: HashMap<String, Vec<TsTypeElement>> = HashMap::new();
+    let mut namespace_imports = HashMap::new();
+    for item in body {
+        if let ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+            specifiers, src, ..
+        })) = item
+        {
+            for spec in specifiers {
+                if let ImportSpecifier::Namespace(ImportStarAsSpecifier {
+                    local: Ident { sym, .. },
+                    ..
+                }) = spec
+                {
+                    namespace_imports.insert(sym.to_string(), src.value.to_string());
+                }
+            }
+        }
+
+        let decl = match item {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { decl, .. }))
+            | ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+            _ => continue,
+        };
+        match decl {
+            Decl::TsInterface(iface) => {
+                let TsInterfaceDecl {
+                    id: Ident { sym, .. },
+                    body: TsInterfaceBody { body, .. },
+                    ..
+                } = iface.as_ref();
+                members
+                    .entry(sym.to_string())
+                    .or_default()
+                    .extend(body.iter().cloned());
+            }
+            Decl::TsTypeAlias(alias) => {
+                let TsTypeAliasDecl {
+                    id: Ident { sym, .. },
+                    type_ann,
+                    ..
+                } = alias.as_ref();
+                if let TsType::TsTypeLit(TsTypeLit { members: body, .. }) = type_ann.as_ref() {
+                    members
+                        .entry(sym.to_string())
+                        .or_default()
+                        .extend(body.iter().cloned());
+                }
+            }
+            _ => {}
+        }
+    }
+    MEMBERS.with(|m| *m.borrow_mut() = members);
+    NAMESPACE_IMPORTS.with(|n| *n.borrow_mut() = namespace_imports);
+
+    let mut superclasses = HashSet::new();
+    collect_superclasses(body, &mut superclasses);
+    SUPERCLASSES.with(|s| *s.borrow_mut() = superclasses);
+
+    let mut enums = HashSet::new();
+    collect_enums(body, "", &mut enums);
+    ENUMS.with(|e| *e.borrow_mut() = enums);
+
+    let mut types = HashSet::new();
+    collect_types(body, "", &mut types);
+    TYPES.with(|t| *t.borrow_mut() = types);
+    SCOPE.with(|s| s.borrow_mut().clear());
+}
+
+fn collect_enums(body: &[ModuleItem], prefix: &str, enums: &mut HashSet<String>) {
+    for item in body {
+        let decl = match item {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { decl, .. }))
+            | ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+            _ => continue,
+        };
+        match decl {
+            Decl::TsEnum(e) => {
+                enums.insert(format!("{prefix}{}", e.id.sym));
+            }
+            Decl::TsModule(module) => {
+                let TsModuleName::Ident(Ident { sym, .. }) = &module.id else {
+                    continue;
+                };
+                if let Some(TsNamespaceBody::TsModuleBlock(block)) = module.body.as_ref() {
+                    collect_enums(&block.body, &format!("{prefix}{sym}."), enums);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn collect_types(body: &[ModuleItem], prefix: &str, types: &mut HashSet<String>) {
+    for item in body {
+        let decl = match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl { specifiers, .. })) => {
+                for spec in specifiers {
+                    let (ImportSpecifier::Named(ImportNamedSpecifier { local, .. })
+                    | ImportSpecifier::Default(ImportDefaultSpecifier { local, .. })
+                    | ImportSpecifier::Namespace(ImportStarAsSpecifier { local, .. })) = spec;
+                    types.insert(format!("{prefix}{}", local.sym));
+                }
+                continue;
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { decl, .. }))
+            | ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+            _ => continue,
+        };
+        let sym = match decl {
+            Decl::Class(ClassDecl { ident, .. }) => &ident.sym,
+            Decl::TsInterface(iface) => &iface.id.sym,
+            Decl::TsTypeAlias(alias) => &alias.id.sym,
+            Decl::TsEnum(e) => &e.id.sym,
+            Decl::TsModule(module) => {
+                let TsModuleName::Ident(Ident { sym, .. }) = &module.id else {
+                    continue;
+                };
+                if let Some(TsNamespaceBody::TsModuleBlock(block)) = module.body.as_ref() {
+                    collect_types(&block.body, &format!("{prefix}{sym}."), types);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        types.insert(format!("{prefix}{sym}"));
+    }
+}
+
+fn collect_superclasses(body: &[ModuleItem], superclasses: &mut HashSet<String>) {
+    for item in body {
+        let decl = match item {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { decl, .. }))
+            | ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+            _ => continue,
+        };
+        match decl {
+            Decl::Class(ClassDecl { class, .. }) => {
+                if let Some(Ident { sym, .. }) =
+                    class.super_class.as_ref().and_then(|c| c.as_ident())
+                {
+                    superclasses.insert(sym.to_string());
+                }
+            }
+            Decl::TsModule(module) => {
+                let mut ns_body = module.body.as_ref();
+                while let Some(TsNamespaceBody::TsNamespaceDecl(ns)) = ns_body {
+                    ns_body = Some(&ns.body);
+                }
+                if let Some(TsNamespaceBody::TsModuleBlock(block)) = ns_body {
+                    collect_superclasses(&block.body, superclasses);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Where a namespace imported with `import * as X from "..."` is imported from
+pub fn namespace_import(name: &str) -> Option<String> {
+    NAMESPACE_IMPORTS.with(|n| n.borrow().get(name).cloned())
+}
+
+/// Convert the contents of a namespace, resolving names relative to it
+pub fn in_namespace<R>(name: &str, f: impl FnOnce() -> R) -> R {
+    SCOPE.with(|s| s.borrow_mut().push(name.to_string()));
+    let converted = f();
+    SCOPE.with(|s| s.borrow_mut().pop());
+    converted
+}
+
+/// Namespaces enclosing the declarations being converted, outermost first
+pub fn namespace_scope() -> Vec<String> {
+    SCOPE.with(|s| s.borrow().clone())
+}
+
+/// Whether a name, as written in the namespace being converted, is an enum declared in the file
+///
+/// Like Typescript, it's looked up in the enclosing namespaces from the innermost out,
+/// so `Kind` in `namespace a` may be `a.Kind`, but not `b.Kind`.
+pub fn is_enum(name: &str) -> bool {
+    let scope = namespace_scope();
+    ENUMS.with(|e| {
+        let enums = e.borrow();
+        (0..=scope.len()).rev().any(|depth| {
+            let qualified = scope[..depth]
+                .iter()
+                .map(String::as_str)
+                .chain([name])
+                .collect::<Vec<_>>()
+                .join(".");
+            enums.contains(&qualified)
+        })
+    })
+}
+
+/// Declare a type generated in the namespace being converted, unless a type by that name
+/// already is declared there
+pub fn declare_type(name: &str) -> bool {
+    let qualified = namespace_scope()
+        .iter()
+        .map(String::as_str)
+        .chain([name])
+        .collect::<Vec<_>>()
+        .join(".");
+    TYPES.with(|t| t.borrow_mut().insert(qualified))
+}
+
+/// Whether another class in the file extends the class
+pub fn is_subclassed(class: &str) -> bool {
+    SUPERCLASSES.with(|s| s.borrow().contains(class))
+}
+
+/// The type of a property on a declared interface or object type alias,
+/// and whether it is optional
+pub fn member_type(owner: &str, member: &str) -> Option<(TsType, bool)> {
+    MEMBERS.with(|m| find_member(m.borrow().get(owner)?, member))
+}
+
+/// The type of a property on an object type literal or a reference to a declared one,
+/// and whether it is optional
+pub fn type_member(ty: &TsType, member: &str) -> Option<(TsType, bool)> {
+    match ty {
+        TsType::TsTypeLit(TsTypeLit { members, .. }) => find_member(members, member),
+        TsType::TsTypeRef(TsTypeRef {
+            type_name: TsEntityName::Ident(Ident { sym, .. }),
+            ..
+        }) => member_type(sym, member),
+        _ => None,
+    }
+}
+
+fn find_member(elems: &[TsTypeElement], member: &str) -> Option<(TsType, bool)> {
+    elems.iter().find_map(|elem| match elem {
+        TsTypeElement::TsPropertySignature(TsPropertySignature {
+            key,
+            type_ann: Some(type_ann),
+            optional,
+            ..
+        }) if key_name(key) == Some(member) => Some((*type_ann.type_ann.clone(), *optional)),
+        _ => None,
+    })
+}
+
+fn key_name(key: &Expr) -> Option<&str> {
+    match key {
+        Expr::Ident(Ident { sym, .. }) => Some(sym),
+        Expr::Lit(Lit::Str(Str { value, .. })) => Some(value),
+        _ => None,
+    }
+}
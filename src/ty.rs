@@ -1,34 +1,116 @@
 use std::collections::HashSet;
 
+use log::warn;
+use swc_common::{EqIgnoreSpan, Spanned};
 use swc_ecma_ast::{
-    ArrayPat, BindingIdent, Ident, ObjectPat, Pat, RestPat, Str, TsEntityName,
-    TsFnOrConstructorType, TsFnParam, TsFnType, TsImportType, TsIntersectionType,
-    TsKeywordTypeKind, TsTupleElement, TsTupleType, TsType, TsTypeRef, TsUnionOrIntersectionType,
+    ArrayPat, BindingIdent, Ident, ObjectPat, Pat, RestPat, Str, TsArrayType, TsConditionalType,
+    TsConstructorType, TsEntityName, TsFnOrConstructorType, TsFnParam, TsFnType, TsImportType,
+    TsIndexedAccessType, TsInferType, TsIntersectionType, TsKeywordType, TsKeywordTypeKind, TsLit,
+    TsLitType, TsMappedType, TsParenthesizedType, TsRestType, TsTupleElement, TsTupleType, TsType,
+    TsTypeAnn, TsTypeOperator, TsTypeOperatorOp, TsTypeParamDecl, TsTypePredicate, TsTypeRef,
+    TsUnionOrIntersectionType,
 };
 use syn::{
     parse_quote, parse_str,
     punctuated::Punctuated,
     token::{Colon2, Comma},
     visit_mut::VisitMut,
-    GenericArgument, Path, PathArguments, PathSegment, Type, TypePath,
+    GenericArgument, Path, PathArguments, PathSegment, Type, TypeParen, TypePath, TypeReference,
 };
 
 use crate::{
+    config::config,
+    jsdoc::snippet,
+    notes::{fallback_note, note},
+    stats::fallback,
+    symbols::{is_enum, member_type, namespace_import},
     util::{
-        import_path_to_type_path_prefix, sanitize_sym, ByeByeGenerics, KNOWN_JS_SYS_TYPES,
-        KNOWN_STRING_TYPES, KNOWN_WEB_SYS_TYPES,
+        import_path_to_type_path_prefix, module_name, option_of, sanitize_sym, ByeByeGenerics,
+        KNOWN_JS_SYS_TYPES, KNOWN_STRING_TYPES, KNOWN_WEB_SYS_TYPES,
     },
-    wasm::js_value,
+    wasm::{js_value, wasm_bindgen_path},
 };
+/// Whether a return type is `void`, `never` or `asserts x`, which may as well not return anything
+pub fn returns_nothing(ty: &TsType) -> bool {
+    matches!(
+        ty,
+        TsType::TsKeywordType(TsKeywordType {
+            kind: TsKeywordTypeKind::TsVoidKeyword | TsKeywordTypeKind::TsNeverKeyword,
+            ..
+        }) | TsType::TsTypePredicate(TsTypePredicate { asserts: true, .. })
+    )
+}
+
+/// A name qualified by its namespaces, i.e. `ns.Kind`
+fn entity_name(name: &TsEntityName) -> String {
+    match name {
+        TsEntityName::TsQualifiedName(quali) => {
+            format!("{}.{}", entity_name(&quali.left), quali.right.sym)
+        }
+        TsEntityName::Ident(Ident { sym, .. }) => sym.to_string(),
+    }
+}
+
+/// Whether a type is a `&dyn Fn(..)` closure
+pub fn is_closure(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(TypeReference { elem, .. }) => match elem.as_ref() {
+            Type::Paren(TypeParen { elem, .. }) => matches!(elem.as_ref(), Type::TraitObject(_)),
+            elem => matches!(elem, Type::TraitObject(_)),
+        },
+        _ => false,
+    }
+}
+
+/// Replace `&dyn Fn(..)` closures with [js_sys] `Function`s where JS may keep the callback,
+/// since closures passed to JS are only valid during the call
+pub struct ClosuresToFunctions;
+
+impl VisitMut for ClosuresToFunctions {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if is_closure(ty) {
+            *ty = parse_quote!(Function);
+        } else {
+            syn::visit_mut::visit_type_mut(self, ty);
+        }
+    }
+}
+
+impl ClosuresToFunctions {
+    /// Only closures passed directly as a param can be borrowed for the call,
+    /// so just their own params & returns become `Function`s
+    pub fn visit_param_mut(&mut self, ty: &mut Type) {
+        if is_closure(ty) {
+            syn::visit_mut::visit_type_mut(self, ty);
+        } else {
+            self.visit_type_mut(ty);
+        }
+    }
+}
+
 pub fn ts_type_to_type(ty: &TsType) -> Type {
+    let converted = convert_ts_type(ty);
+    // `any` & co are JsValues anyway
+    if converted == js_value().into() && !matches!(ty, TsType::TsKeywordType(_)) {
+        fallback(ty.span());
+        if config(|c| c.note_fallbacks) {
+            let source = snippet(ty.span()).unwrap_or_else(|| "?".to_string());
+            note(fallback_note(&source));
+        }
+    }
+    converted
+}
+
+fn convert_ts_type(ty: &TsType) -> Type {
     match ty {
         TsType::TsKeywordType(kt) => match kt.kind {
             TsKeywordTypeKind::TsUnknownKeyword
             | TsKeywordTypeKind::TsAnyKeyword
             | TsKeywordTypeKind::TsNullKeyword
             | TsKeywordTypeKind::TsUndefinedKeyword
-            | TsKeywordTypeKind::TsNeverKeyword
-            | TsKeywordTypeKind::TsObjectKeyword => js_value().into(),
+            | TsKeywordTypeKind::TsNeverKeyword => js_value().into(),
+            // Any non-primitive
+            TsKeywordTypeKind::TsObjectKeyword => parse_quote!(Object),
             TsKeywordTypeKind::TsNumberKeyword => parse_quote!(::core::primitive::f64),
             TsKeywordTypeKind::TsBooleanKeyword => parse_quote!(::core::primitive::bool),
             TsKeywordTypeKind::TsStringKeyword => parse_quote!(::std::string::String),
@@ -42,36 +124,42 @@ pub fn ts_type_to_type(ty: &TsType) -> Type {
             TsFnOrConstructorType::TsFnType(TsFnType {
                 params,
                 type_params,
-                // TODO: insert this return type on the signature
                 type_ann,
                 ..
+            }) => fn_type(params, type_params, type_ann),
+            // i.e. `abstract new () => Base`, which is only there to be extended
+            TsFnOrConstructorType::TsConstructorType(TsConstructorType {
+                span,
+                is_abstract: true,
+                ..
             }) => {
-                let mut gen = ByeByeGenerics::new(type_params.iter());
-                let mut inputs: Punctuated<Type, Comma> = Punctuated::new();
-                for p in params {
-                    let ty = match p {
-                        TsFnParam::Ident(BindingIdent { type_ann, .. })
-                        // TODO: how to mark this as variadic :(
-                        | TsFnParam::Rest(RestPat { type_ann, .. })
-                        | TsFnParam::Array(ArrayPat { type_ann, .. })
-                        | TsFnParam::Object(ObjectPat { type_ann , ..} )=> {
-                            type_ann.as_ref().map(|ann| ts_type_to_type(&ann.type_ann))
-                        }
-                    };
-                    inputs.push(ty.unwrap_or_else(|| js_value().into()));
-                }
-                inputs.iter_mut().for_each(|i| gen.visit_type_mut(i));
-                parse_quote! {
-                    &(dyn Fn(#inputs))
-                }
+                let shape = snippet(*span).unwrap_or_else(|| "abstract new (...)".to_string());
+                note(format!(
+                    "Abstract constructor type `{shape}` is bound as `Function`, since it can't be called"
+                ));
+                parse_quote!(Function)
             }
-            TsFnOrConstructorType::TsConstructorType(ct) => todo!("{ct:?}"),
+            // Bound like a function returning the constructed type, i.e. `new (opts: Opts) => Widget`
+            TsFnOrConstructorType::TsConstructorType(TsConstructorType {
+                params,
+                type_params,
+                type_ann,
+                ..
+            }) => fn_type(params, type_params, type_ann),
         },
         TsType::TsTypeRef(TsTypeRef {
+            span,
             type_name,
             type_params,
-            ..
         }) => match type_name {
+            // `E.A` where `E` is an enum is the type of that member, bound as the enum
+            TsEntityName::TsQualifiedName(quali) if is_enum(&entity_name(&quali.left)) => {
+                ts_type_to_type(&TsType::TsTypeRef(TsTypeRef {
+                    span: *span,
+                    type_name: quali.left.clone(),
+                    type_params: None,
+                }))
+            }
             qn @ TsEntityName::TsQualifiedName(_) => {
                 let mut type_path: Punctuated<PathSegment, Colon2> = Punctuated::new();
 
@@ -85,8 +173,18 @@ pub fn ts_type_to_type(ty: &TsType) -> Type {
                     syms.push(&ident.sym);
                 }
 
-                for sym in syms[1..].iter().rev() {
-                    let revised_raw_name = format!("{}Mod", sym);
+                let (leading, namespaces) = syms[1..].split_last().unwrap();
+                // `X.Y` where X is `import * as X from "./x"` refers to a type in that module
+                if let Some(src) = namespace_import(leading) {
+                    type_path = import_path_to_type_path_prefix(&src);
+                } else {
+                    type_path.push(PathSegment {
+                        ident: sanitize_sym(&module_name(leading)),
+                        arguments: PathArguments::None,
+                    });
+                }
+                for sym in namespaces.iter().rev() {
+                    let revised_raw_name = module_name(sym);
                     type_path.push(PathSegment {
                         ident: sanitize_sym(&revised_raw_name),
                         arguments: PathArguments::None,
@@ -123,22 +221,19 @@ pub fn ts_type_to_type(ty: &TsType) -> Type {
                 }
             }
         },
-        TsType::TsTypeQuery(tq) => {
-            eprintln!("Type queries unsupported");
+        TsType::TsTypeQuery(_tq) => {
+            warn!("Type queries unsupported");
             js_value().into()
         }
-        TsType::TsTypeLit(tl) => {
-            eprintln!("Type literals unsupported");
+        TsType::TsTypeLit(_tl) => {
+            warn!("Type literals unsupported");
             js_value().into()
         }
         TsType::TsArrayType(at) => {
             let elem_ty = ts_type_to_type(&at.elem_type);
             parse_quote!(::std::boxed::Box<[#elem_ty]>)
         }
-        TsType::TsOptionalType(ot) => {
-            let opt_ty = ts_type_to_type(&ot.type_ann);
-            parse_quote!(::std::option::Option<#opt_ty>)
-        }
+        TsType::TsOptionalType(ot) => option_of(ts_type_to_type(&ot.type_ann)),
         TsType::TsUnionOrIntersectionType(uoi) => match uoi {
             TsUnionOrIntersectionType::TsUnionType(union) => {
                 if union.types.len() == 2
@@ -154,8 +249,7 @@ pub fn ts_type_to_type(ty: &TsType) -> Type {
                         })
                         .unwrap_or(false)
                 {
-                    let opt_ty = ts_type_to_type(&union.types[0]);
-                    parse_quote!(::std::option::Option<#opt_ty>)
+                    option_of(ts_type_to_type(&union.types[0]))
                 } else {
                     js_value().into()
                 }
@@ -164,7 +258,7 @@ pub fn ts_type_to_type(ty: &TsType) -> Type {
                 if let Some(ty) = types.first() {
                     return ts_type_to_type(ty);
                 }
-                eprintln!("Empty intersection type");
+                warn!("Empty intersection type");
                 js_value().into()
             }
         },
@@ -172,10 +266,16 @@ pub fn ts_type_to_type(ty: &TsType) -> Type {
             let pty = ts_type_to_type(&pt.type_ann);
             parse_quote!((#pty))
         }
-        TsType::TsLitType(_tlt) => {
-            eprintln!("Lit types unsupported");
-            js_value().into()
-        }
+        // i.e. `port: 8080` in `as const` types, which is just a number
+        TsType::TsLitType(TsLitType { lit, .. }) => match lit {
+            TsLit::Number(_) => parse_quote!(::core::primitive::f64),
+            TsLit::Str(_) | TsLit::Tpl(_) => parse_quote!(::std::string::String),
+            TsLit::Bool(_) => parse_quote!(::core::primitive::bool),
+            TsLit::BigInt(_) => {
+                warn!("BigInt lit types unsupported");
+                js_value().into()
+            }
+        },
 
         TsType::TsImportType(TsImportType {
             arg: Str { value, .. },
@@ -183,8 +283,23 @@ pub fn ts_type_to_type(ty: &TsType) -> Type {
             ..
         }) => {
             if !value.starts_with('.') {
-                eprintln!("Import unknown");
-                js_value().into()
+                let known = qualifier
+                    .as_ref()
+                    .map(|q| match q {
+                        TsEntityName::Ident(Ident { sym, .. }) => sanitize_sym(sym),
+                        TsEntityName::TsQualifiedName(q) => sanitize_sym(&q.right.sym),
+                    })
+                    .filter(|ident| {
+                        let ident = ident.to_string();
+                        KNOWN_WEB_SYS_TYPES.contains(ident.as_str())
+                            || KNOWN_JS_SYS_TYPES.contains(ident.as_str())
+                    });
+                if let Some(ident) = known {
+                    parse_quote!(#ident)
+                } else {
+                    warn!("Import unknown");
+                    js_value().into()
+                }
             } else {
                 let path = import_path_to_type_path_prefix(value);
                 let ident = sanitize_sym(&qualifier.as_ref().unwrap().as_ident().unwrap().sym);
@@ -200,25 +315,270 @@ pub fn ts_type_to_type(ty: &TsType) -> Type {
             }
             parse_quote!((#types))
         }
-        TsType::TsIndexedAccessType(_iat) => {
-            eprintln!("Indexed access type unsupported");
-            js_value().into()
+        TsType::TsIndexedAccessType(TsIndexedAccessType {
+            obj_type,
+            index_type,
+            ..
+        }) => {
+            // i.e. `Config["timeout"]`
+            let member = match (obj_type.as_ref(), index_type.as_ref()) {
+                (
+                    TsType::TsTypeRef(TsTypeRef {
+                        type_name: TsEntityName::Ident(Ident { sym: owner, .. }),
+                        ..
+                    }),
+                    TsType::TsLitType(TsLitType {
+                        lit: TsLit::Str(Str { value: member, .. }),
+                        ..
+                    }),
+                ) => member_type(owner, member),
+                _ => None,
+            };
+            match member {
+                Some((member_ty, optional)) => {
+                    let ty = ts_type_to_type(&member_ty);
+                    if optional {
+                        option_of(ty)
+                    } else {
+                        ty
+                    }
+                }
+                None => {
+                    warn!("Indexed access type unsupported");
+                    js_value().into()
+                }
+            }
         }
         TsType::TsInferType(_) => js_value().into(),
         TsType::TsThisType(_) => {
             parse_quote!(Self)
         }
-        TsType::TsRestType(_)
-        | TsType::TsTypePredicate(_)
-        | TsType::TsConditionalType(_)
-        | TsType::TsTypeOperator(_)
-        | TsType::TsMappedType(_) => todo!("{ty:?}"),
+        TsType::TsConditionalType(TsConditionalType {
+            check_type,
+            extends_type,
+            true_type,
+            ..
+        }) => {
+            let mut inferred = InferredTypes::default();
+            if conditional_always_true(check_type, extends_type) {
+                ts_type_to_type(true_type)
+            } else if inferred.matches(check_type, extends_type) {
+                let mut ty = ts_type_to_type(true_type);
+                inferred.visit_type_mut(&mut ty);
+                ty
+            } else {
+                warn!("Conditional types unsupported");
+                js_value().into()
+            }
+        }
+        // i.e. `...B[]` in `[A, ...B[]]`
+        TsType::TsRestType(TsRestType { type_ann, .. }) => match type_ann.as_ref() {
+            TsType::TsArrayType(_) => ts_type_to_type(type_ann),
+            _ => {
+                let js_value = js_value();
+                parse_quote!(::std::boxed::Box<[#js_value]>)
+            }
+        },
+        // Type guards like `x is Foo` are booleans at runtime, but `asserts x` returns nothing
+        TsType::TsTypePredicate(TsTypePredicate { asserts: false, .. }) => {
+            parse_quote!(::core::primitive::bool)
+        }
+        TsType::TsTypePredicate(TsTypePredicate { asserts: true, .. }) => parse_quote!(()),
+        TsType::TsTypeOperator(TsTypeOperator { op, type_ann, .. }) => match op {
+            // Keys could technically be symbols too
+            TsTypeOperatorOp::KeyOf => parse_quote!(::std::string::String),
+            TsTypeOperatorOp::ReadOnly => ts_type_to_type(type_ann),
+            // `unique symbol`
+            TsTypeOperatorOp::Unique => parse_quote!(Symbol),
+        },
+        TsType::TsMappedType(TsMappedType { span, .. }) => {
+            let shape = snippet(*span).unwrap_or_else(|| "{ ... }".to_string());
+            note(format!("Mapped type `{shape}` is bound as `Object`"));
+            parse_quote!(Object)
+        }
+    }
+}
+
+/// `Box<[T]>` for a rest param typed as an array of `T`, or `Box<[JsValue]>` otherwise
+fn rest_type(type_ann: Option<&TsTypeAnn>) -> Type {
+    let is_boxed_slice = |ty: &Type| match ty {
+        Type::Path(TypePath { qself: None, path }) => path.segments.last().is_some_and(|seg| {
+            seg.ident == "Box"
+                && matches!(&seg.arguments, PathArguments::AngleBracketed(args)
+                    if matches!(args.args.first(), Some(GenericArgument::Type(Type::Slice(_)))))
+        }),
+        _ => false,
+    };
+    match type_ann.map(|ann| ts_type_to_type(&ann.type_ann)) {
+        Some(ty) if is_boxed_slice(&ty) => ty,
+        _ => {
+            let js_value = js_value();
+            parse_quote!(::std::boxed::Box<[#js_value]>)
+        }
+    }
+}
+
+/// `&dyn Fn(..) -> T` for function & constructor types
+fn fn_type(
+    params: &[TsFnParam],
+    type_params: &Option<Box<TsTypeParamDecl>>,
+    type_ann: &TsTypeAnn,
+) -> Type {
+    let mut gen = ByeByeGenerics::new(type_params.iter());
+    let mut inputs: Punctuated<Type, Comma> = Punctuated::new();
+    for p in params {
+        let ty = match p {
+            // Closures can't be variadic, so the rest are passed as one array
+            TsFnParam::Rest(RestPat { type_ann, .. }) => Some(rest_type(type_ann.as_deref())),
+            TsFnParam::Ident(BindingIdent { type_ann, .. })
+            | TsFnParam::Array(ArrayPat { type_ann, .. })
+            | TsFnParam::Object(ObjectPat { type_ann, .. }) => {
+                type_ann.as_ref().map(|ann| ts_type_to_type(&ann.type_ann))
+            }
+        };
+        inputs.push(ty.unwrap_or_else(|| js_value().into()));
+    }
+    inputs.iter_mut().for_each(|i| gen.visit_type_mut(i));
+    if returns_nothing(&type_ann.type_ann) {
+        parse_quote! {
+            &(dyn Fn(#inputs))
+        }
+    } else {
+        let mut output = ts_type_to_type(&type_ann.type_ann);
+        gen.visit_type_mut(&mut output);
+        parse_quote! {
+            &(dyn Fn(#inputs) -> #output)
+        }
+    }
+}
+
+/// Best-effort check for conditional types that always take the true branch
+///
+/// i.e. `T extends any ? X : Y` or `T extends T ? X : Y`. A check type of `any` or `unknown`
+/// doesn't qualify, since `any` takes both branches & `unknown` only extends the top types.
+fn conditional_always_true(check_type: &TsType, extends_type: &TsType) -> bool {
+    let is_top = |ty: &TsType| {
+        matches!(
+            ty.as_ts_keyword_type().map(|k| k.kind),
+            Some(TsKeywordTypeKind::TsAnyKeyword | TsKeywordTypeKind::TsUnknownKeyword)
+        )
+    };
+    is_top(extends_type) || check_type.eq_ignore_span(extends_type)
+}
+
+/// The types `infer X` placeholders stand for in a conditional type's true branch
+///
+/// i.e. `U` is `string` in `string[] extends Array<infer U> ? U : never`
+#[derive(Default)]
+struct InferredTypes(Vec<(syn::Ident, Type)>);
+
+impl InferredTypes {
+    /// Whether `check` is known to match `pattern`, binding its placeholders along the way
+    fn matches(&mut self, check: &TsType, pattern: &TsType) -> bool {
+        /// The element type of `T[]`, `Array<T>` & their readonly forms
+        fn array_elem(ty: &TsType) -> Option<&TsType> {
+            match ty {
+                TsType::TsArrayType(TsArrayType { elem_type, .. }) => Some(elem_type),
+                TsType::TsTypeOperator(TsTypeOperator {
+                    op: TsTypeOperatorOp::ReadOnly,
+                    type_ann,
+                    ..
+                }) => array_elem(type_ann),
+                TsType::TsTypeRef(TsTypeRef {
+                    type_name: TsEntityName::Ident(Ident { sym, .. }),
+                    type_params: Some(params),
+                    ..
+                }) if (&**sym == "Array" || &**sym == "ReadonlyArray")
+                    && params.params.len() == 1 =>
+                {
+                    Some(&params.params[0])
+                }
+                _ => None,
+            }
+        }
+
+        match (check, pattern) {
+            (_, TsType::TsInferType(TsInferType { type_param, .. })) => {
+                self.0
+                    .push((sanitize_sym(&type_param.name.sym), ts_type_to_type(check)));
+                true
+            }
+            (TsType::TsParenthesizedType(TsParenthesizedType { type_ann, .. }), _) => {
+                self.matches(type_ann, pattern)
+            }
+            (_, TsType::TsParenthesizedType(TsParenthesizedType { type_ann, .. })) => {
+                self.matches(check, type_ann)
+            }
+            _ if array_elem(check).is_some() && array_elem(pattern).is_some() => {
+                self.matches(array_elem(check).unwrap(), array_elem(pattern).unwrap())
+            }
+            (
+                TsType::TsTypeRef(TsTypeRef {
+                    type_name: check_name,
+                    type_params: check_params,
+                    ..
+                }),
+                TsType::TsTypeRef(TsTypeRef {
+                    type_name: pattern_name,
+                    type_params: pattern_params,
+                    ..
+                }),
+            ) if check_name.eq_ignore_span(pattern_name) => match (check_params, pattern_params) {
+                (None, None) => true,
+                (Some(check), Some(pattern)) if check.params.len() == pattern.params.len() => check
+                    .params
+                    .iter()
+                    .zip(&pattern.params)
+                    .all(|(check, pattern)| self.matches(check, pattern)),
+                _ => false,
+            },
+            (
+                TsType::TsTupleType(TsTupleType {
+                    elem_types: check, ..
+                }),
+                TsType::TsTupleType(TsTupleType {
+                    elem_types: pattern,
+                    ..
+                }),
+            ) if check.len() == pattern.len() => check
+                .iter()
+                .zip(pattern)
+                .all(|(check, pattern)| self.matches(&check.ty, &pattern.ty)),
+            // i.e. `(...args: any[]) => infer R`, where only the return type is of interest
+            (
+                TsType::TsFnOrConstructorType(TsFnOrConstructorType::TsFnType(TsFnType {
+                    type_ann: check,
+                    ..
+                })),
+                TsType::TsFnOrConstructorType(TsFnOrConstructorType::TsFnType(TsFnType {
+                    type_ann: pattern,
+                    ..
+                })),
+            ) => self.matches(&check.type_ann, &pattern.type_ann),
+            _ => conditional_always_true(check, pattern),
+        }
+    }
+}
+
+impl VisitMut for InferredTypes {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if let Type::Path(TypePath { qself: None, path }) = ty {
+            if let Some(ident) = path.get_ident() {
+                if let Some((_, inferred)) = self.0.iter().find(|(name, _)| name == ident) {
+                    *ty = inferred.clone();
+                    return;
+                }
+            }
+        }
+        syn::visit_mut::visit_type_mut(self, ty);
     }
 }
 
 pub fn wasm_abi_set(custom: &HashSet<String>) -> HashSet<Type> {
     thread_local! {
-        static SLICEABLE_BUILTINS: [Type; 8] = [
+        static SLICEABLE_BUILTINS: [Type; 12] = [
+            parse_quote!(::core::primitive::i8), parse_quote!(::core::primitive::i16),
+            parse_quote!(::core::primitive::u8), parse_quote!(::core::primitive::u16),
             parse_quote!(::core::primitive::i32), parse_quote!(::core::primitive::isize), parse_quote!(::core::primitive::i64),
             parse_quote!(::core::primitive::u32), parse_quote!(::core::primitive::usize), parse_quote!(::core::primitive::u64),
             parse_quote!(::core::primitive::f32), parse_quote!(::core::primitive::f64),
@@ -261,6 +621,22 @@ pub fn wasm_abi_set(custom: &HashSet<String>) -> HashSet<Type> {
             .chain(KNOWN_TYPES.with(|t| t.clone()))
             .chain(js_objects.clone())
             .map::<Type, _>(|t| parse_quote!(::std::option::Option<::std::boxed::Box<[#t]>>));
+        // Typed arrays lowered by `TypedArraySlices`
+        let slices = builtins.iter().map::<Type, _>(|t| parse_quote!(&[#t]));
+        // Those & the arrays returned by `VecReturns`
+        let vecs = builtins
+            .iter()
+            .cloned()
+            .chain(KNOWN_TYPES.with(|t| t.clone()))
+            .chain(js_objects.clone())
+            .chain(std::iter::once(js_value().into()))
+            .map::<Type, _>(|t| parse_quote!(::std::vec::Vec<#t>));
+        let opt_vecs = builtins
+            .iter()
+            .cloned()
+            .chain(KNOWN_TYPES.with(|t| t.clone()))
+            .chain(js_objects.clone())
+            .map::<Type, _>(|t| parse_quote!(::std::option::Option<::std::vec::Vec<#t>>));
 
         builtins
             .iter()
@@ -272,7 +648,23 @@ pub fn wasm_abi_set(custom: &HashSet<String>) -> HashSet<Type> {
             .chain(opts)
             .chain(boxed_slices)
             .chain(opt_boxed_slices)
+            .chain(slices)
+            .chain(vecs)
+            .chain(opt_vecs)
+            .chain({
+                let wasm_bindgen = wasm_bindgen_path();
+                [
+                    // Index signature keys
+                    parse_quote!(&::core::primitive::str),
+                    parse_quote!(#wasm_bindgen::Clamped<&[::core::primitive::u8]>),
+                    parse_quote!(#wasm_bindgen::Clamped<::std::vec::Vec<::core::primitive::u8>>),
+                ]
+            })
             .chain(std::iter::once(js_value().into()))
+            .chain(std::iter::once({
+                let js_value = js_value();
+                parse_quote!(::std::boxed::Box<[#js_value]>)
+            }))
             .collect()
     })
 }
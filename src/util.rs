@@ -1,21 +1,29 @@
 use std::collections::{HashMap, HashSet};
 
 use lazy_static::lazy_static;
+use log::warn;
 use swc_ecma_ast::TsTypeParamDecl;
+use syn::ext::IdentExt;
 use syn::{
-    parse_quote, parse_str, punctuated::Punctuated, token::Colon2, visit::Visit,
-    visit_mut::VisitMut, AngleBracketedGenericArguments, Attribute, ExprPath, FnArg, ForeignItem,
-    GenericArgument, Ident, ItemUse, PatType, PathArguments, PathSegment, ReturnType, Token, Type,
-    TypePath, TypeReference, TypeSlice, UseName, UseRename, __private::ToTokens,
+    __private::ToTokens, parse_quote, parse_str, punctuated::Punctuated, token::Colon2,
+    visit::Visit, visit_mut::VisitMut, AngleBracketedGenericArguments, Attribute, Expr, ExprAssign,
+    ExprPath, FnArg, ForeignItem, ForeignItemFn, ForeignItemType, GenericArgument, Ident, Item,
+    ItemEnum, ItemForeignMod, ItemImpl, ItemMod, ItemUse, Pat, PatType, PathArguments, PathSegment,
+    ReturnType, Token, Type, TypePath, TypeReference, TypeSlice, UseGroup, UseName, UsePath,
+    UseRename, UseTree, Visibility,
 };
 
-use crate::wasm::{extends, js_value, merge_attrs, method_of};
+use crate::config::config;
+use crate::notes::{attach_notes, fallback_note, note};
+use crate::wasm::{
+    catch_ok, extends, js_value, merge_attrs, method_of, wasm_bindgen_args, wasm_bindgen_path,
+};
 
 /// Makes a JS ident a valid Rust ident.
 /// Also changes casing to match [web_sys] & [js_sys].
 pub fn sanitize_sym(sym: &str) -> Ident {
     let ident = match sym {
-        "self" | "super" | "crate" => format!("{sym}_rs"),
+        "self" | "Self" | "super" | "crate" => format!("{sym}_rs"),
         _ => {
             let mut sanitized_sym = String::new();
             let mut prev_cap = false;
@@ -26,8 +34,7 @@ pub fn sanitize_sym(sym: &str) -> Ident {
             for c in sym.chars() {
                 let next = next_it.next();
                 sanitized_sym.push(
-                    if !all_upper && prev_cap && next.map_or(true, |next| next.is_ascii_uppercase())
-                    {
+                    if !all_upper && prev_cap && next.is_none_or(|next| next.is_ascii_uppercase()) {
                         c.to_ascii_lowercase()
                     } else {
                         c
@@ -35,14 +42,83 @@ pub fn sanitize_sym(sym: &str) -> Ident {
                 );
                 prev_cap = c.is_ascii_uppercase() || c.is_ascii_digit();
             }
+            // Keys like `0` or `"data-id"` aren't identifiers
+            let mut sanitized_sym: String = sanitized_sym
+                .chars()
+                .map(|c| {
+                    if c.is_alphanumeric() || c == '_' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            if sanitized_sym.starts_with(|c: char| c.is_ascii_digit()) {
+                sanitized_sym.insert(0, '_');
+            }
             sanitized_sym
         }
     };
+    // syn 1 predates some keywords, so it'd parse i.e. `async` & `dyn` as plain idents
+    if KEYWORDS.contains(&ident.as_str()) {
+        return parse_str(&format!("r#{ident}")).expect(&ident);
+    }
     parse_str(&ident)
         .or_else(|_| parse_str(&format!("r#{ident}")))
         .expect(&ident)
 }
 
+/// Name of an accessor, i.e. `get_x` or `set_x`. The prefix makes names like `self` or `0`
+/// valid idents, so they aren't suffixed or prefixed with `_`
+pub fn accessor_name(prefix: &str, raw_name: &str) -> Ident {
+    let name = match raw_name {
+        "self" | "Self" | "super" | "crate" => raw_name.to_string(),
+        _ => {
+            let name = sanitize_sym(raw_name).unraw().to_string();
+            if raw_name.starts_with(|c: char| c.is_ascii_digit()) {
+                name.trim_start_matches('_').to_string()
+            } else {
+                name
+            }
+        }
+    };
+    sanitize_sym(&format!("{prefix}_{name}"))
+}
+
+/// Rust keywords (strict, reserved, & from later editions) that need to be raw idents
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+/// Converts a JS ident to a SCREAMING_SNAKE_CASE Rust ident for statics.
+pub fn screaming_snake_case(sym: &str) -> Ident {
+    let chars: Vec<char> = sym.chars().collect();
+    let mut screaming = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if i > 0 && c.is_ascii_uppercase() {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase());
+            if prev.is_ascii_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_ascii_uppercase() && next_lower)
+            {
+                screaming.push('_');
+            }
+        }
+        screaming.push(c.to_ascii_uppercase());
+    }
+    sanitize_sym(&screaming)
+}
+
+/// Name of the module a file or namespace is bound as
+pub fn module_name(raw_name: &str) -> String {
+    config(|c| format!("{raw_name}{}", c.module_suffix))
+}
+
 pub fn import_prefix_to_idents(path: &str) -> Vec<Ident> {
     let mut acc = vec![];
     let mut first_dot_dot = true;
@@ -56,7 +132,7 @@ pub fn import_prefix_to_idents(path: &str) -> Vec<Ident> {
                 first_dot_dot = false;
             }
         } else {
-            let seg = format!("{}Mod", seg.strip_suffix(".js").unwrap_or(seg));
+            let seg = module_name(seg.strip_suffix(".js").unwrap_or(seg));
             acc.push(parse_str(&seg).unwrap());
         }
     }
@@ -77,7 +153,7 @@ pub fn import_path_to_type_path_prefix(path: &str) -> Punctuated<PathSegment, Co
                 first_dot_dot = false;
             }
         } else {
-            let seg = format!("{}Mod", seg.strip_suffix(".js").unwrap_or(seg));
+            let seg = module_name(seg.strip_suffix(".js").unwrap_or(seg));
             acc.push(parse_str(&seg).expect(&seg));
         }
     }
@@ -90,6 +166,7 @@ pub fn import_path_to_type_path_prefix(path: &str) -> Punctuated<PathSegment, Co
 /// * Flatten `Option<Option<_>>`
 /// * Replace known TypeScript string union types with string
 /// * Make `Option<JsValue>` `JsValue`
+/// * Make `Option<()>` `()`
 pub struct BindingsCleaner;
 
 impl VisitMut for BindingsCleaner {
@@ -118,13 +195,19 @@ impl VisitMut for BindingsCleaner {
                 *t = t_inner.clone();
                 self.visit_type_mut(t);
             }
-            Type::Path(t) => self.visit_type_path_mut(t),
+            Type::Path(p) => {
+                self.visit_type_path_mut(p);
+                // There's nothing to be optional about
+                if *t == parse_quote!(::std::option::Option<()>) {
+                    *t = parse_quote!(());
+                }
+            }
             Type::Ptr(t) => self.visit_type_ptr_mut(t),
             Type::Reference(t) => self.visit_type_reference_mut(t),
             Type::Slice(t) => self.visit_type_slice_mut(t),
             Type::TraitObject(t) => self.visit_type_trait_object_mut(t),
             Type::Tuple(t) => self.visit_type_tuple_mut(t),
-            other => todo!("{other:?}"),
+            other => warn!("Unexpected type left as is: {}", other.to_token_stream()),
         }
     }
 
@@ -136,17 +219,9 @@ impl VisitMut for BindingsCleaner {
                     args, ..
                 }) = &seg.arguments
                 {
-                    if let GenericArgument::Type(Type::Path(
-                        inner @ TypePath {
-                            path: inner_path, ..
-                        },
-                    )) = args.first().unwrap()
-                    {
-                        if inner_path.leading_colon.is_some() {
-                            let last = &inner_path.segments.last().unwrap().ident;
-                            if last == "Option" || last == "JsValue" {
-                                *t = inner.clone();
-                            }
+                    if let GenericArgument::Type(Type::Path(inner)) = args.first().unwrap() {
+                        if is_nullable_path(inner) {
+                            *t = inner.clone();
                         }
                     }
                 }
@@ -165,10 +240,30 @@ impl VisitMut for BindingsCleaner {
     }
 }
 
+/// Whether the type is already an `Option` or `JsValue`, which can hold `null`/`undefined` as is
+fn is_nullable_path(t: &TypePath) -> bool {
+    if t.path.leading_colon.is_none() {
+        return false;
+    }
+    let last = &t.path.segments.last().unwrap().ident;
+    last == "Option" || last == "JsValue"
+}
+
+/// `Option<T>`, unless `T` can already be `null`/`undefined` or is `()`
+pub fn option_of(t: Type) -> Type {
+    match &t {
+        Type::Path(path) if is_nullable_path(path) => t,
+        Type::Tuple(tuple) if tuple.elems.is_empty() => t,
+        _ => parse_quote!(::std::option::Option<#t>),
+    }
+}
+
 /// Removes the given generics
 pub struct ByeByeGenerics(pub Vec<Ident>);
 
 impl ByeByeGenerics {
+    /// Only the names are needed, modifiers like `in`/`out` variance & constraints are dropped
+    /// along with the params
     pub fn new<'a>(args: impl Iterator<Item = &'a Box<TsTypeParamDecl>>) -> Self {
         Self(
             args.flat_map(|tp| tp.params.iter())
@@ -198,21 +293,93 @@ impl VisitMut for ByeByeGenerics {
     }
 }
 
+/// * Drop items with identical signatures (i.e. repeated overloads)
 /// * Dedupe items with the same name
 /// * Replace Self with class name
+/// * Keep the members of each type together after it, merging repeated declarations of the type
 #[derive(Default)]
-pub struct ModuleBindingsCleaner(HashMap<Option<syn::Path>, HashSet<String>>);
+pub struct ModuleBindingsCleaner {
+    names: HashMap<Option<syn::Path>, HashSet<String>>,
+    signatures: HashMap<Option<syn::Path>, HashSet<String>>,
+}
 
-impl VisitMut for ModuleBindingsCleaner {
-    fn visit_signature_mut(&mut self, sig: &mut syn::Signature) {
-        let class_type = if let Some(FnArg::Typed(PatType { ty, .. })) = sig.inputs.first() {
-            if let Type::Reference(ty) = ty.as_ref() {
-                ty.elem.as_ref()
-            } else {
-                return;
+impl ModuleBindingsCleaner {
+    pub fn clean(&mut self, items: &mut Vec<ForeignItem>) {
+        items.retain_mut(|fi| {
+            if let ForeignItem::Fn(ff) = fi {
+                let signature = ff.to_token_stream().to_string();
+                if !self
+                    .signatures
+                    .entry(method_of(ff))
+                    .or_default()
+                    .insert(signature)
+                {
+                    return false;
+                }
             }
-        } else {
-            return;
+            self.visit_foreign_item_mut(fi);
+            true
+        });
+        group_members(items);
+    }
+}
+
+/// Move methods, accessors & constructors right after the type they belong to, in order
+///
+/// A class & an interface with the same name (or a class declared in pieces) are one type,
+/// so only the first declaration is kept, with the attributes of the rest.
+fn group_members(items: &mut Vec<ForeignItem>) {
+    let type_names: HashSet<Ident> = items
+        .iter()
+        .filter_map(|item| match item {
+            ForeignItem::Type(ty) => Some(ty.ident.clone()),
+            _ => None,
+        })
+        .collect();
+    // Everything else is in a group of its own
+    let mut groups: Vec<(Option<ForeignItemType>, Vec<ForeignItem>)> = vec![];
+    let mut group_of: HashMap<Ident, usize> = HashMap::new();
+    for item in items.drain(..) {
+        let owner = match &item {
+            ForeignItem::Type(ty) => Some(ty.ident.clone()),
+            ForeignItem::Fn(ff) => method_of(ff)
+                .and_then(|path| path.get_ident().cloned())
+                .filter(|ident| type_names.contains(ident)),
+            _ => None,
+        };
+        let Some(owner) = owner else {
+            groups.push((None, vec![item]));
+            continue;
+        };
+        let group = *group_of.entry(owner).or_insert_with(|| {
+            groups.push((None, vec![]));
+            groups.len() - 1
+        });
+        let (ty, members) = &mut groups[group];
+        match (item, ty) {
+            (ForeignItem::Type(new), Some(ty)) => {
+                for attr in new.attrs {
+                    if !ty.attrs.contains(&attr) {
+                        ty.attrs.push(attr);
+                    }
+                }
+            }
+            (ForeignItem::Type(new), ty) => *ty = Some(new),
+            (member, _) => members.push(member),
+        }
+    }
+
+    for (ty, members) in groups {
+        items.extend(ty.map(ForeignItem::Type));
+        items.extend(members);
+    }
+}
+
+impl VisitMut for ModuleBindingsCleaner {
+    fn visit_foreign_item_fn_mut(&mut self, ff: &mut ForeignItemFn) {
+        let class_path = match method_of(ff) {
+            Some(class_path) => class_path,
+            None => return,
         };
 
         struct SelfToClass(Type);
@@ -223,14 +390,20 @@ impl VisitMut for ModuleBindingsCleaner {
                         let seg = tp.path.segments.first_mut().unwrap();
                         if seg.ident == "Self" && seg.arguments.is_empty() {
                             *t = self.0.clone();
+                            return;
                         }
                     }
                 }
+                // i.e. Option<Self>
+                syn::visit_mut::visit_type_mut(self, t);
             }
         }
 
-        let mut stc = SelfToClass(class_type.clone());
-        stc.visit_signature_mut(sig);
+        let mut stc = SelfToClass(Type::Path(TypePath {
+            qself: None,
+            path: class_path,
+        }));
+        stc.visit_signature_mut(&mut ff.sig);
     }
 
     fn visit_foreign_item_mut(&mut self, fi: &mut ForeignItem) {
@@ -253,18 +426,109 @@ impl VisitMut for ModuleBindingsCleaner {
         let mut ident_string = ident.to_string();
         let mut counter = 1;
 
-        let entries = self.0.entry(clazz).or_default();
+        let entries = self.names.entry(clazz).or_default();
         while entries.contains(&ident_string) {
             ident_string = format!("{ident}_{counter}");
             counter += 1;
         }
         if counter > 1 {
+            // The JS name may have been inferred from the Rust name
+            let args = wasm_bindgen_args(attrs);
+            let has_js_name = args.iter().any(
+                |arg| matches!(arg, Expr::Assign(ExprAssign { left, .. }) if **left == parse_quote!(js_name)),
+            );
+            // Constructors are named after their class
+            if !has_js_name && !args.contains(&parse_quote!(constructor)) {
+                let name = ident.to_string();
+                let name = name.trim_start_matches("r#");
+                let raw_name = if args.contains(&parse_quote!(setter)) {
+                    name.strip_prefix("set_").unwrap_or(name)
+                } else {
+                    name
+                };
+                attrs.push(parse_quote!(#[wasm_bindgen(js_name = #raw_name)]));
+            }
             *ident = parse_str(&ident_string).unwrap();
         }
         entries.insert(ident_string);
     }
 }
 
+/// Asserts at compile time that every extern type implements `JsCast`
+pub fn add_js_cast_assertions(items: &mut Vec<Item>) {
+    let wasm_bindgen = wasm_bindgen_path();
+    let mut assertions: Vec<Item> = vec![];
+    for item in items.iter_mut() {
+        match item {
+            Item::ForeignMod(ItemForeignMod { items, .. }) => {
+                for fi in items {
+                    if let ForeignItem::Type(ForeignItemType { ident, .. }) = fi {
+                        assertions.push(parse_quote! {
+                            const _: () = {
+                                const fn _assert<T: #wasm_bindgen::JsCast>() {}
+                                _assert::<#ident>();
+                            };
+                        });
+                    }
+                }
+            }
+            Item::Mod(ItemMod {
+                content: Some((_, items)),
+                ..
+            }) => add_js_cast_assertions(items),
+            _ => {}
+        }
+    }
+    items.append(&mut assertions);
+}
+
+/// Drops private uses that nothing refers to,
+/// i.e. a [js_sys] type that [WasmAbify] replaced with `JsValue`
+///
+/// Exported uses are kept since they're re-exports.
+pub fn remove_unused_uses(items: &mut Vec<Item>) {
+    #[derive(Default)]
+    struct CollectIdents(HashSet<String>);
+    impl<'ast> Visit<'ast> for CollectIdents {
+        fn visit_ident(&mut self, ident: &'ast Ident) {
+            self.0.insert(ident.to_string());
+        }
+
+        fn visit_item_use(&mut self, _: &'ast ItemUse) {}
+
+        fn visit_attribute(&mut self, attr: &'ast Attribute) {
+            self.visit_path(&attr.path);
+            wasm_bindgen_args(std::slice::from_ref(attr))
+                .iter()
+                .for_each(|arg| self.visit_expr(arg));
+        }
+    }
+
+    fn used_name(tree: &UseTree, used: &HashSet<String>) -> bool {
+        match tree {
+            UseTree::Path(UsePath { tree, .. }) => used_name(tree, used),
+            UseTree::Name(UseName { ident }) | UseTree::Rename(UseRename { rename: ident, .. }) => {
+                used.contains(&ident.to_string())
+            }
+            UseTree::Glob(_) => true,
+            UseTree::Group(UseGroup { items, .. }) => {
+                items.iter().any(|tree| used_name(tree, used))
+            }
+        }
+    }
+
+    let mut idents = CollectIdents::default();
+    items.iter().for_each(|i| idents.visit_item(i));
+    items.retain(|item| match item {
+        Item::Use(ItemUse {
+            vis: Visibility::Inherited,
+            tree,
+            ..
+        }) => used_name(tree, &idents.0),
+        _ => true,
+    });
+}
+
 /// Collects all the names exported by a module
 #[derive(Default)]
 pub struct CollectPubs(pub HashSet<String>);
@@ -278,6 +542,18 @@ impl<'ast> Visit<'ast> for CollectPubs {
         self.0.insert(rn.rename.to_string());
     }
 
+    fn visit_item_enum(&mut self, ie: &'ast ItemEnum) {
+        self.0.insert(ie.ident.to_string());
+    }
+
+    fn visit_type_path(&mut self, t: &'ast TypePath) {
+        // Types in other files (i.e. `X.Y` for `import * as X`) are trusted like imports are
+        if t.qself.is_none() && t.path.segments.first().unwrap().ident == "super" {
+            self.0.insert(t.to_token_stream().to_string());
+        }
+        syn::visit::visit_type_path(self, t);
+    }
+
     fn visit_foreign_item(&mut self, fi: &'ast ForeignItem) {
         match fi {
             ForeignItem::Fn(f) => {
@@ -291,6 +567,7 @@ impl<'ast> Visit<'ast> for CollectPubs {
             }
             _ => {}
         }
+        syn::visit::visit_foreign_item(self, fi);
     }
 }
 
@@ -298,7 +575,16 @@ impl<'ast> Visit<'ast> for CollectPubs {
 pub struct SysUseAdder {
     /// Items that the module exports
     pub pubs: HashSet<String>,
-    pub uses: HashSet<ItemUse>,
+    /// In the order the names are first used, so the output is the same every time
+    pub uses: Vec<ItemUse>,
+}
+
+impl SysUseAdder {
+    fn add(&mut self, item_use: ItemUse) {
+        if !self.uses.contains(&item_use) {
+            self.uses.push(item_use);
+        }
+    }
 }
 
 impl<'ast> Visit<'ast> for SysUseAdder {
@@ -309,12 +595,14 @@ impl<'ast> Visit<'ast> for SysUseAdder {
             let seg_ident_string = seg.ident.to_string();
             if !self.pubs.contains(&seg_ident_string) {
                 if KNOWN_WEB_SYS_TYPES.contains(&seg_ident_string.as_str()) {
-                    self.uses.insert(parse_quote! {
-                        use ::web_sys:: #seg_ident;
+                    let web_sys = config(|c| c.web_sys_path.clone());
+                    self.add(parse_quote! {
+                        use #web_sys:: #seg_ident;
                     });
                 } else if KNOWN_JS_SYS_TYPES.contains(&seg_ident_string.as_str()) {
-                    self.uses.insert(parse_quote! {
-                        use ::js_sys:: #seg_ident;
+                    let js_sys = config(|c| c.js_sys_path.clone());
+                    self.add(parse_quote! {
+                        use #js_sys:: #seg_ident;
                     });
                 }
             }
@@ -325,7 +613,7 @@ impl<'ast> Visit<'ast> for SysUseAdder {
     }
 
     fn visit_attribute(&mut self, attr: &'ast Attribute) {
-        if let Some(ExprPath { path, .. }) = extends(attr) {
+        for ExprPath { path, .. } in extends(attr) {
             self.visit_type_path(&TypePath { qself: None, path });
         }
     }
@@ -386,6 +674,14 @@ impl<'ast> Visit<'ast> for NestedTyFinder<'ast> {
 }
 
 impl VisitMut for WasmAbify {
+    // Helpers generated alongside the bindings are plain Rust
+    fn visit_item_impl_mut(&mut self, _: &mut ItemImpl) {}
+
+    fn visit_foreign_item_mut(&mut self, fi: &mut ForeignItem) {
+        syn::visit_mut::visit_foreign_item_mut(self, fi);
+        attach_notes(std::slice::from_mut(fi));
+    }
+
     fn visit_return_type_mut(&mut self, rt: &mut ReturnType) {
         // Can't return references
         if let ReturnType::Type(_, ty) = rt {
@@ -393,10 +689,27 @@ impl VisitMut for WasmAbify {
             tyf.visit_type(ty);
             if let Some(Type::Reference(TypeReference { elem, .. })) = tyf.result {
                 if let Type::TraitObject(_) = elem.as_ref() {
-                    *ty = Box::new(js_value().into());
+                    **ty = js_value().into();
                     return;
                 }
             }
+            // Only the ok type of `catch` functions needs checking
+            if catch_ok(ty) != ty.as_ref() {
+                if let Type::Path(TypePath { path, .. }) = ty.as_mut() {
+                    if let PathArguments::AngleBracketed(AngleBracketedGenericArguments {
+                        args,
+                        ..
+                    }) = &mut path.segments.last_mut().unwrap().arguments
+                    {
+                        if let Some(GenericArgument::Type(ok)) = args.first_mut() {
+                            if *ok != parse_quote!(()) {
+                                self.visit_type_mut(ok);
+                            }
+                        }
+                    }
+                }
+                return;
+            }
             self.visit_type_mut(ty);
         }
     }
@@ -409,12 +722,147 @@ impl VisitMut for WasmAbify {
             }
         }
         if !self.wasm_abi_types.contains(t) {
-            eprintln!("Missing {}", t.into_token_stream());
+            warn!("Missing {}", t.into_token_stream());
+            if config(|c| c.note_fallbacks) {
+                note(fallback_note(&type_source(t)));
+            }
             *t = js_value().into();
         }
     }
 }
 
+/// A type as it'd be written, i.e. `::std::boxed::Box<[Storage]>`
+fn type_source(ty: &Type) -> String {
+    let unparsed = prettyplease::unparse(&parse_quote!(type T = #ty;));
+    let source = unparsed
+        .trim()
+        .strip_prefix("type T = ")
+        .and_then(|source| source.strip_suffix(';'))
+        .unwrap_or(&unparsed);
+    source.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Element type of a typed array that [wasm_bindgen] can copy to & from a slice
+///
+/// `Uint8ClampedArray` is left out, its slices are wrapped in `Clamped`.
+fn typed_array_elem(name: &str) -> Option<Type> {
+    Some(match name {
+        "Int8Array" => parse_quote!(::core::primitive::i8),
+        "Uint8Array" => parse_quote!(::core::primitive::u8),
+        "Int16Array" => parse_quote!(::core::primitive::i16),
+        "Uint16Array" => parse_quote!(::core::primitive::u16),
+        "Int32Array" => parse_quote!(::core::primitive::i32),
+        "Uint32Array" => parse_quote!(::core::primitive::u32),
+        "Float32Array" => parse_quote!(::core::primitive::f32),
+        "Float64Array" => parse_quote!(::core::primitive::f64),
+        "BigInt64Array" => parse_quote!(::core::primitive::i64),
+        "BigUint64Array" => parse_quote!(::core::primitive::u64),
+        _ => return None,
+    })
+}
+
+/// Lowers [js_sys] typed array params to slices (i.e. `&[i32]`) and returns to [Vec]s
+pub struct TypedArraySlices {
+    /// Items that the module exports, which shadow the [js_sys] types
+    pub pubs: HashSet<String>,
+}
+
+impl TypedArraySlices {
+    fn typed_array(&self, ty: &Type) -> Option<String> {
+        match ty {
+            Type::Path(TypePath { qself: None, path }) if path.segments.len() == 1 => {
+                let seg = path.segments.first().unwrap();
+                let name = seg.ident.to_string();
+                (seg.arguments.is_empty() && !self.pubs.contains(&name)).then_some(name)
+            }
+            _ => None,
+        }
+    }
+
+    fn param(&self, ty: &Type) -> Option<Type> {
+        let name = self.typed_array(ty)?;
+        if name == "Uint8ClampedArray" {
+            let wasm_bindgen = wasm_bindgen_path();
+            return Some(parse_quote!(#wasm_bindgen::Clamped<&[::core::primitive::u8]>));
+        }
+        let elem = typed_array_elem(&name)?;
+        Some(parse_quote!(&[#elem]))
+    }
+
+    fn ret(&self, ty: &Type) -> Option<Type> {
+        let name = self.typed_array(ty)?;
+        if name == "Uint8ClampedArray" {
+            let wasm_bindgen = wasm_bindgen_path();
+            return Some(parse_quote!(
+                #wasm_bindgen::Clamped<::std::vec::Vec<::core::primitive::u8>>
+            ));
+        }
+        let elem = typed_array_elem(&name)?;
+        Some(parse_quote!(::std::vec::Vec<#elem>))
+    }
+}
+
+impl VisitMut for TypedArraySlices {
+    fn visit_foreign_item_fn_mut(&mut self, ff: &mut ForeignItemFn) {
+        for input in ff.sig.inputs.iter_mut() {
+            if let FnArg::Typed(PatType { pat, ty, .. }) = input {
+                // The receiver of a method stays the typed array
+                if matches!(pat.as_ref(), Pat::Ident(i) if i.ident == "this") {
+                    continue;
+                }
+                if let Some(slice) = self.param(ty) {
+                    **ty = slice;
+                }
+            }
+        }
+        if let ReturnType::Type(_, ty) = &mut ff.sig.output {
+            if let Some(vec) = self.ret(ty) {
+                **ty = vec;
+            }
+        }
+    }
+}
+
+/// Returns arrays as [Vec]s instead of boxed slices, since they're owned anyway
+pub struct VecReturns;
+
+impl VecReturns {
+    /// `Box<[T]>` to `Vec<T>`, looking inside `Option`s & the ok type of `catch` functions
+    fn vec_of(ty: &mut Type) {
+        let Type::Path(TypePath { qself: None, path }) = ty else {
+            return;
+        };
+        let last = path.segments.last_mut().unwrap();
+        let PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, .. }) =
+            &mut last.arguments
+        else {
+            return;
+        };
+        match args.first_mut() {
+            Some(GenericArgument::Type(Type::Slice(TypeSlice { elem, .. })))
+                if last.ident == "Box" =>
+            {
+                let elem = elem.clone();
+                *ty = parse_quote!(::std::vec::Vec<#elem>);
+            }
+            Some(GenericArgument::Type(inner))
+                if last.ident == "Option" || last.ident == "Result" =>
+            {
+                Self::vec_of(inner)
+            }
+            _ => {}
+        }
+    }
+}
+
+impl VisitMut for VecReturns {
+    fn visit_foreign_item_fn_mut(&mut self, ff: &mut ForeignItemFn) {
+        if let ReturnType::Type(_, ty) = &mut ff.sig.output {
+            Self::vec_of(ty);
+        }
+    }
+}
+
 lazy_static! {
     pub static ref KNOWN_STRING_TYPES: HashSet<&'static str> = [
         "AlignSetting",
@@ -2,14 +2,37 @@
 
 use std::cmp::Ordering;
 
+use crate::config::config;
+
 use syn::{
+    __private::ToTokens,
+    parse::{ParseStream, Result as ParseResult},
     parse_quote,
     punctuated::Punctuated,
     token::{Bang, Bracket, Comma, Pound},
-    AttrStyle, Attribute, Expr, ExprAssign, ExprPath, FnArg, ForeignItem, ForeignItemFn, Ident,
-    Pat, PatType, Path, ReturnType, Type, TypePath, TypeReference,
+    AngleBracketedGenericArguments, AttrStyle, Attribute, Expr, ExprAssign, ExprPath, FnArg,
+    ForeignItem, ForeignItemFn, GenericArgument, Ident, Pat, PatType, Path, PathArguments,
+    ReturnType, Token, Type, TypePath, TypeReference,
 };
 
+/// Parse `#[wasm_bindgen(...)]` arguments, which can include keywords like `final`
+fn parse_wasm_bindgen_args(input: ParseStream) -> ParseResult<Punctuated<Expr, Comma>> {
+    let mut args = Punctuated::new();
+    while !input.is_empty() {
+        if input.peek(Token![final]) {
+            let keyword: Token![final] = input.parse()?;
+            args.push_value(Expr::Verbatim(keyword.into_token_stream()));
+        } else {
+            args.push_value(input.parse()?);
+        }
+        if input.is_empty() {
+            break;
+        }
+        args.push_punct(input.parse()?);
+    }
+    Ok(args)
+}
+
 pub fn merge_attrs(fi: &mut ForeignItem) {
     let attrs = match fi {
         ForeignItem::Fn(ff) => &mut ff.attrs,
@@ -35,7 +58,13 @@ pub fn merge_attrs(fi: &mut ForeignItem) {
     let mut wasm_attrs: Punctuated<Expr, Comma> = Punctuated::new();
     let partition_point = attrs.partition_point(not_wasm_attr);
     while attrs.len() > partition_point {
-        wasm_attrs.push(attrs.pop().unwrap().parse_args::<Expr>().unwrap())
+        wasm_attrs.extend(
+            attrs
+                .pop()
+                .unwrap()
+                .parse_args_with(parse_wasm_bindgen_args)
+                .unwrap(),
+        )
     }
 
     if !wasm_attrs.is_empty() {
@@ -46,24 +75,43 @@ pub fn merge_attrs(fi: &mut ForeignItem) {
             path: parse_quote!(wasm_bindgen),
             tokens: parse_quote!(),
         });
-    }
-    *fi = parse_quote! {
-        #[wasm_bindgen(#wasm_attrs)]
-        #fi
+        *fi = parse_quote! {
+            #[wasm_bindgen(#wasm_attrs)]
+            #fi
+        }
     }
 }
 
-pub fn extends(attr: &Attribute) -> Option<ExprPath> {
-    if attr.path.get_ident() == Some(&parse_quote!(wasm_bindgen)) {
-        if let Ok(ExprAssign { left, right, .. }) = attr.parse_args::<ExprAssign>() {
-            if left == parse_quote!(extends) {
-                if let Expr::Path(path) = right.as_ref() {
-                    return Some(path.clone());
+/// The arguments of all the `#[wasm_bindgen(...)]` attributes
+pub fn wasm_bindgen_args(attrs: &[Attribute]) -> Vec<Expr> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.get_ident() == Some(&parse_quote!(wasm_bindgen)))
+        .filter_map(|attr| attr.parse_args_with(parse_wasm_bindgen_args).ok())
+        .flatten()
+        .collect()
+}
+
+/// The types a `#[wasm_bindgen(...)]` attribute extends
+pub fn extends(attr: &Attribute) -> Vec<ExprPath> {
+    if attr.path.get_ident() != Some(&parse_quote!(wasm_bindgen)) {
+        return vec![];
+    }
+    let args = match attr.parse_args_with(parse_wasm_bindgen_args) {
+        Ok(args) => args,
+        Err(_) => return vec![],
+    };
+    args.into_iter()
+        .filter_map(|arg| match arg {
+            Expr::Assign(ExprAssign { left, right, .. }) if *left == parse_quote!(extends) => {
+                match *right {
+                    Expr::Path(path) => Some(path),
+                    _ => None,
                 }
             }
-        }
-    }
-    None
+            _ => None,
+        })
+        .collect()
 }
 
 pub fn method_of(ff: &ForeignItemFn) -> Option<Path> {
@@ -78,7 +126,7 @@ pub fn method_of(ff: &ForeignItemFn) -> Option<Path> {
             } else if let Ok(ident) = attr.parse_args::<Ident>() {
                 if ident == "constructor" {
                     if let ReturnType::Type(_, t) = &ff.sig.output {
-                        if let Type::Path(TypePath { path, .. }) = t.as_ref() {
+                        if let Type::Path(TypePath { path, .. }) = catch_ok(t) {
                             return Some(path.clone());
                         }
                     }
@@ -100,10 +148,48 @@ pub fn method_of(ff: &ForeignItemFn) -> Option<Path> {
     None
 }
 
-pub fn js_value() -> TypePath {
-    thread_local! {
-        static JS_VALUE: TypePath = parse_quote!(::wasm_bindgen::JsValue);
+/// The ok type if this is the `Result` returned by a `catch` function
+pub fn catch_ok(ty: &Type) -> &Type {
+    if let Type::Path(TypePath { qself: None, path }) = ty {
+        let last = path.segments.last().unwrap();
+        if last.ident == "Result" {
+            if let PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, .. }) =
+                &last.arguments
+            {
+                if let Some(GenericArgument::Type(ok)) = args.first() {
+                    return ok;
+                }
+            }
+        }
     }
+    ty
+}
 
-    JS_VALUE.with(Clone::clone)
+pub fn js_value() -> TypePath {
+    let wasm_bindgen = wasm_bindgen_path();
+    parse_quote!(#wasm_bindgen::JsValue)
+}
+
+/// Path to the [wasm_bindgen] crate, `::wasm_bindgen` unless configured otherwise
+pub fn wasm_bindgen_path() -> Path {
+    config(|c| c.wasm_bindgen_path.clone())
+}
+
+/// `#[wasm_bindgen(js_name = raw_name)]`, unless it's the JS name [wasm_bindgen] infers anyway
+///
+/// That's the Rust name, except for setters where the `set_` prefix is stripped.
+pub fn js_name_attr(inferred: &str, raw_name: &str) -> Option<Attribute> {
+    (config(|c| c.always_js_name) || inferred != raw_name)
+        .then(|| parse_quote!(#[wasm_bindgen(js_name = #raw_name)]))
+}
+
+/// The `#[wasm_bindgen]` attribute for exported items & extern blocks
+pub fn wasm_bindgen_attr() -> Attribute {
+    let wasm_bindgen = wasm_bindgen_path();
+    // The macro has to be told where wasm_bindgen is when it's been re-exported
+    if wasm_bindgen != parse_quote!(::wasm_bindgen) {
+        parse_quote!(#[wasm_bindgen(wasm_bindgen = #wasm_bindgen)])
+    } else {
+        parse_quote!(#[wasm_bindgen])
+    }
 }
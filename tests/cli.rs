@@ -0,0 +1,495 @@
+//! Running the command line tool on trees of declarations
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+/// A fresh directory for a test, with `files` written to its `ts` directory
+fn project(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join("cli")
+        .join(name);
+    let _ = fs::remove_dir_all(&dir);
+    for (path, contents) in files {
+        let path = dir.join("ts").join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    dir
+}
+
+/// Run the tool in `dir`
+fn run(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_wasm-bindgen-ts-decl"))
+        .current_dir(dir)
+        .env_remove("RUST_LOG")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Run the tool in `dir`, piping `input` to it
+fn run_with_stdin(dir: &Path, args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_wasm-bindgen-ts-decl"))
+        .current_dir(dir)
+        .env_remove("RUST_LOG")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn dry_run_writes_nothing() {
+    let dir = project(
+        "dry_run_writes_nothing",
+        &[
+            ("a.d.ts", "export declare function a(): void;"),
+            ("sub/b.d.ts", "export declare class B { b(): void }"),
+        ],
+    );
+    let output = run(&dir, &["ts", "out", "--dry-run"]);
+    assert!(output.status.success());
+    assert!(!dir.join("out").exists());
+    let stdout = stdout(&output);
+    assert!(stdout.contains("out/a.rs (1 items)"), "{stdout}");
+    assert!(stdout.contains("out/sub/b.rs (2 items)"), "{stdout}");
+
+    fs::write(dir.join("ts/c.d.ts"), "export declare function (: void;").unwrap();
+    let output = run(&dir, &["ts", "out", "--dry-run"]);
+    assert!(!output.status.success());
+    assert!(!dir.join("out").exists());
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn enums_exported_by_several_files() {
+    let dir = project(
+        "enums_exported_by_several_files",
+        &[
+            ("a.d.ts", "export declare enum Kind { A }"),
+            ("b.d.ts", "export declare enum Kind { B }"),
+            ("c.d.ts", "export declare namespace c { enum Kind { C } }"),
+        ],
+    );
+    let output = run(&dir, &["ts", "out", "-v"]);
+    assert!(output.status.success());
+    let stderr = stderr(&output);
+    assert!(
+        stderr.contains("The `Kind` enum is exported by ts/a.d.ts, ts/b.d.ts"),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("c_Kind"), "{stderr}");
+}
+
+#[test]
+fn single_file() {
+    let dir = project(
+        "single_file",
+        &[
+            ("a.d.ts", "export declare function a(): void;"),
+            ("sub/b.d.ts", "export declare class B {}"),
+        ],
+    );
+    let output = run(&dir, &["ts", "bindings.rs", "--single-file"]);
+    assert!(output.status.success());
+    assert!(!dir.join("bindings").exists());
+
+    let bindings = fs::read_to_string(dir.join("bindings.rs")).unwrap();
+    let file = syn::parse_file(&bindings).unwrap();
+    let modules: Vec<_> = file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Mod(m) => Some(m.ident.to_string()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(modules, ["aMod", "subMod"]);
+    assert!(bindings.contains("pub mod bMod {"), "{bindings}");
+    assert!(bindings.contains("pub type B;"), "{bindings}");
+}
+
+#[test]
+fn unsupported_constructs_warn_when_verbose() {
+    let dir = project(
+        "unsupported_constructs_warn_when_verbose",
+        &[(
+            "a.d.ts",
+            "export declare function f<T>(x: T): T extends string ? number : boolean;",
+        )],
+    );
+    let output = run(&dir, &["ts", "out"]);
+    assert!(output.status.success());
+    assert_eq!(stderr(&output), "");
+
+    let output = run(&dir, &["ts", "out", "-v", "--force"]);
+    assert!(output.status.success());
+    let stderr = stderr(&output);
+    assert!(
+        stderr.contains("[WARN ] Conditional types unsupported"),
+        "{stderr}"
+    );
+    // Progress is only logged at -vv
+    assert!(!stderr.contains("[INFO ]"), "{stderr}");
+}
+
+/// Modification times of the files in a directory
+fn mtimes(dir: &Path) -> Vec<(PathBuf, std::time::SystemTime)> {
+    let mut mtimes = vec![];
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            mtimes.extend(self::mtimes(&path));
+        } else {
+            let modified = fs::metadata(&path).unwrap().modified().unwrap();
+            mtimes.push((path, modified));
+        }
+    }
+    mtimes.sort();
+    mtimes
+}
+
+#[test]
+fn unchanged_inputs_are_skipped() {
+    let dir = project(
+        "unchanged_inputs_are_skipped",
+        &[
+            ("a.d.ts", "export declare function a(): HTMLElement;"),
+            ("sub/b.d.ts", "export declare class B { b(): Storage }"),
+        ],
+    );
+    assert!(run(&dir, &["ts", "out"]).status.success());
+    let manifest = fs::read_to_string(dir.join("out/.wasm-bindgen-ts-decl")).unwrap();
+    let first = mtimes(&dir.join("out"));
+    std::thread::sleep(std::time::Duration::from_millis(50));
+
+    let output = run(&dir, &["ts", "out", "-vv"]);
+    assert!(output.status.success());
+    let stderr = stderr(&output);
+    assert!(stderr.contains("ts/a.d.ts (unchanged)"), "{stderr}");
+    assert!(stderr.contains("ts/sub/b.d.ts (unchanged)"), "{stderr}");
+    assert_eq!(mtimes(&dir.join("out")), first);
+    assert_eq!(
+        fs::read_to_string(dir.join("out/.wasm-bindgen-ts-decl")).unwrap(),
+        manifest
+    );
+
+    // Forced conversions still don't touch files whose bindings are the same
+    let output = run(&dir, &["ts", "out", "-vv", "--force"]);
+    assert!(!self::stderr(&output).contains("(unchanged)"));
+    assert_eq!(mtimes(&dir.join("out")), first);
+
+    fs::write(dir.join("ts/a.d.ts"), "export declare function a(): void;").unwrap();
+    let output = run(&dir, &["ts", "out", "-vv"]);
+    let stderr = self::stderr(&output);
+    assert!(!stderr.contains("ts/a.d.ts (unchanged)"), "{stderr}");
+    assert!(stderr.contains("ts/sub/b.d.ts (unchanged)"), "{stderr}");
+}
+
+#[test]
+fn changed_references_are_converted() {
+    let dir = project(
+        "changed_references_are_converted",
+        &[
+            (
+                "m.d.ts",
+                "/// <reference path=\"g.d.ts\" />
+export declare function m(): void;",
+            ),
+            ("g.d.ts", "declare function reset(): void;"),
+        ],
+    );
+    assert!(run(&dir, &["ts", "out"]).status.success());
+    let m = fs::read_to_string(dir.join("out/m.rs")).unwrap();
+    assert!(m.contains("pub use super::gMod::reset;"), "{m}");
+
+    // Only the referenced file changes
+    fs::write(
+        dir.join("ts/g.d.ts"),
+        "declare function reset(): void;
+declare function extra(): void;",
+    )
+    .unwrap();
+    let output = run(&dir, &["ts", "out", "-vv"]);
+    let stderr = stderr(&output);
+    assert!(!stderr.contains("ts/m.d.ts (unchanged)"), "{stderr}");
+    let m = fs::read_to_string(dir.join("out/m.rs")).unwrap();
+    assert!(m.contains("pub use super::gMod::{reset, extra};"), "{m}");
+}
+
+#[test]
+fn excluded_paths_arent_converted() {
+    let dir = project(
+        "excluded_paths_arent_converted",
+        &[
+            ("a.d.ts", "export declare function a(): void;"),
+            ("a.test.d.ts", "export declare function test(): void;"),
+            ("internal/b.d.ts", "export declare function b(): void;"),
+        ],
+    );
+    let output = run(
+        &dir,
+        &[
+            "ts",
+            "out",
+            "--exclude",
+            "internal",
+            "--exclude",
+            "**/*.test.d.ts",
+        ],
+    );
+    assert!(output.status.success());
+    assert!(dir.join("out/a.rs").exists());
+    assert!(!dir.join("out/a.test.rs").exists());
+    assert!(!dir.join("out/internal").exists());
+    let root = fs::read_to_string(dir.join("out/mod.rs")).unwrap();
+    assert_eq!(
+        root,
+        "#[path = \"a.rs\"]\n#[allow(non_snake_case)]\npub mod aMod;\n"
+    );
+
+    // Malformed globs are usage errors
+    let output = run(&dir, &["ts", "out2", "--exclude", "**/{a"]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = stderr(&output);
+    assert!(
+        stderr.contains("invalid value '**/{a' for '--exclude <EXCLUDE>'"),
+        "{stderr}"
+    );
+    assert!(!dir.join("out2").exists());
+}
+
+#[test]
+fn referenced_global_scripts() {
+    let dir = project(
+        "referenced_global_scripts",
+        &[
+            (
+                "globals.d.ts",
+                "interface Shared { x: number }
+                declare function reset(): void;
+                declare function local(): void;",
+            ),
+            (
+                "main.d.ts",
+                r#"/// <reference path="globals.d.ts" />
+                export declare function draw(s: Shared): void;
+                export declare function local(): void;"#,
+            ),
+        ],
+    );
+    assert!(run(&dir, &["ts", "out"]).status.success());
+
+    let globals = fs::read_to_string(dir.join("out/globals.rs")).unwrap();
+    assert!(globals.contains("pub type Shared;"), "{globals}");
+    assert!(globals.contains("pub fn reset();"), "{globals}");
+    let main = fs::read_to_string(dir.join("out/main.rs")).unwrap();
+    // `local` is declared in both, so it isn't imported
+    assert!(
+        main.contains("pub use super::globalsMod::{Shared, reset};"),
+        "{main}"
+    );
+    assert!(main.contains("pub fn draw(s: Shared);"), "{main}");
+}
+
+#[test]
+fn prelude_reexports_root_bindings() {
+    let dir = project(
+        "prelude_reexports_root_bindings",
+        &[
+            (
+                "a.d.ts",
+                "export declare class A { m(): void }
+                export declare function make(): A;
+                export declare function shared(): void;",
+            ),
+            (
+                "sub/b.d.ts",
+                "export interface B {}
+                export declare enum Kind { X }
+                export declare function shared(): void;
+                export declare namespace ns { function nested(): void }",
+            ),
+        ],
+    );
+    let output = run(&dir, &["ts", "out", "--prelude"]);
+    assert!(output.status.success());
+    let root = fs::read_to_string(dir.join("out/mod.rs")).unwrap();
+    let prelude = root.split_once("pub mod prelude {").unwrap().1;
+    let uses: Vec<_> = prelude
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("pub use"))
+        .collect();
+    // `shared` is in both files & `m` & `nested` aren't at the root of theirs
+    assert_eq!(
+        uses,
+        [
+            "pub use super::aMod::A;",
+            "pub use super::subMod::bMod::B;",
+            "pub use super::subMod::bMod::Kind;",
+            "pub use super::aMod::make;",
+        ]
+    );
+}
+
+#[test]
+fn stdin_to_stdout() {
+    let dir = project("stdin_to_stdout", &[]);
+    fs::create_dir_all(&dir).unwrap();
+    let output = run_with_stdin(
+        &dir,
+        &["-"],
+        "export declare function greet(name: string): void;",
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "use ::wasm_bindgen::prelude::wasm_bindgen;
+#[wasm_bindgen]
+extern \"C\" {
+    pub fn greet(name: ::std::string::String);
+}
+"
+    );
+    // Nothing's written to the filesystem
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+}
+
+#[test]
+fn stats_of_a_tree() {
+    let dir = project(
+        "stats_of_a_tree",
+        &[
+            (
+                "a.d.ts",
+                "export declare class A { a(): void; [Symbol.hasInstance](): void; }
+export interface I { x: string | number; }
+export declare function f(): void;
+export declare enum E { X }",
+            ),
+            ("b.d.ts", "export declare function g(): void;"),
+        ],
+    );
+    let output = run(&dir, &["ts", "out", "--stats"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stderr(&output),
+        "2 files
+1 classes
+1 interfaces
+2 functions
+1 enums
+1 types bound as JsValue
+  ts/a.d.ts:2:25: string | number
+1 skipped
+  ts/a.d.ts:1:38: Symbol.hasInstance
+"
+    );
+}
+
+#[test]
+fn config_file_in_crate_root() {
+    let dir = project(
+        "config_file_in_crate_root",
+        &[(
+            "a.d.ts",
+            "export declare function f(d: Document): HTMLElement;",
+        )],
+    );
+    fs::write(
+        dir.join("wasm-bindgen-ts-decl.toml"),
+        r#"web-sys-path = "::my_web""#,
+    )
+    .unwrap();
+    let output = run(&dir, &["ts", "out", "--crate-root", "."]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let a = fs::read_to_string(dir.join("out/a.rs")).unwrap();
+    assert!(a.contains("use ::my_web::Document;"), "{a}");
+    assert!(a.contains("use ::my_web::HtmlElement;"), "{a}");
+}
+
+#[test]
+fn tsx_sources() {
+    let dir = project(
+        "tsx_sources",
+        &[
+            (
+                "button.tsx",
+                "export function Button(props: { label: string }): JSX.Element {
+    return <button>{props.label}</button>;
+}
+export const size: number = 1;",
+            ),
+            ("bad.d.tsx", "export declare function bad(): void;"),
+        ],
+    );
+    // Only declarations are converted by default
+    let output = run(&dir, &["ts", "out"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!dir.join("out/button.rs").exists());
+
+    let output = run(&dir, &["ts", "out", "--ts-sources", "-v"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let button = fs::read_to_string(dir.join("out/button.rs")).unwrap();
+    assert!(
+        button
+            .contains("pub fn Button(props: ::wasm_bindgen::JsValue) -> ::wasm_bindgen::JsValue;"),
+        "{button}"
+    );
+    assert!(
+        button.contains("pub static size: ::core::primitive::f64;"),
+        "{button}"
+    );
+    let stderr = self::stderr(&output);
+    assert!(
+        stderr.contains("ts/bad.d.tsx: declaration files can't contain JSX, skipping"),
+        "{stderr}"
+    );
+    assert!(!dir.join("out/bad.rs").exists());
+}
+
+#[test]
+fn invalid_crate_paths() {
+    let dir = project(
+        "invalid_crate_paths",
+        &[("a.d.ts", "export declare function a(): void;")],
+    );
+    let output = run(&dir, &["ts", "out", "--wasm-bindgen-path", "not a path"]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = self::stderr(&output);
+    assert!(
+        stderr.contains("invalid value 'not a path' for '--wasm-bindgen-path"),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("panicked"), "{stderr}");
+
+    fs::write(
+        dir.join("wasm-bindgen-ts-decl.toml"),
+        r#"web-sys-path = "not a path""#,
+    )
+    .unwrap();
+    let output = run(&dir, &["ts", "out", "--crate-root", "."]);
+    assert!(!output.status.success());
+    let stderr = self::stderr(&output);
+    assert!(stderr.contains("wasm-bindgen-ts-decl.toml"), "{stderr}");
+    assert!(!stderr.contains("panicked"), "{stderr}");
+}
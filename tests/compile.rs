@@ -0,0 +1,60 @@
+//! Bindings generated for the declarations in `tests/compile` compile against [wasm_bindgen],
+//! [js_sys], & [web_sys]
+//!
+//! A `.rs` file next to a declaration file is used as the `main` of its bindings,
+//! otherwise they're only checked to build. Some are converted with options on, see [config].
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use wasm_bindgen_ts_decl::{convert_dts_file, set_config, Config};
+
+#[test]
+fn bindings_compile() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/compile");
+    let out_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("compile");
+    fs::create_dir_all(&out_dir).unwrap();
+
+    let mut declarations: Vec<PathBuf> = fs::read_dir(&fixtures)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.to_string_lossy().ends_with(".d.ts"))
+        .collect();
+    declarations.sort();
+
+    let cases = trybuild::TestCases::new();
+    for path in declarations {
+        let name = path.file_name().unwrap().to_string_lossy();
+        let name = name.trim_end_matches(".d.ts");
+        set_config(config(name));
+        let bindings = convert_dts_file(&path).unwrap();
+        let main = fs::read_to_string(fixtures.join(format!("{name}.rs")))
+            .unwrap_or_else(|_| "fn main() {}\n".to_string());
+
+        let out = out_dir.join(format!("{name}.rs"));
+        fs::write(
+            &out,
+            format!(
+                "#![allow(non_snake_case, non_camel_case_types, unused)]\n{}\n{main}",
+                prettyplease::unparse(&bindings)
+            ),
+        )
+        .unwrap();
+        cases.pass(out);
+    }
+}
+
+/// Config for the fixture with this name
+fn config(name: &str) -> Config {
+    match name {
+        "js_cast" => Config {
+            assert_js_cast: true,
+            ..Config::default()
+        },
+        "typed_arrays" => Config {
+            typed_array_slices: true,
+            ..Config::default()
+        },
+        _ => Config::default(),
+    }
+}
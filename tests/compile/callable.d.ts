@@ -0,0 +1,5 @@
+export interface JQuery {
+    (selector: string): JQuery;
+    new (): JQuery;
+    length: number;
+}
@@ -0,0 +1,37 @@
+/** A point in 2D space */
+export interface Point {
+    x: number;
+    y: number;
+    readonly label?: string;
+    move(dx: number, dy: number): void;
+    [key: string]: unknown;
+}
+
+export declare abstract class Shape {
+    abstract area(): number;
+    get name(): string;
+    set name(value: string);
+    get size(): number;
+    set size(value: number | string);
+}
+
+export declare class Circle extends Shape {
+    constructor(center: Point, radius?: number);
+    static unit(): Circle;
+    area(): number;
+    readonly center: Point;
+    radius: number;
+    #secret: string;
+}
+
+export declare function distance(a: Point, b: Point): number;
+export declare function distance(a: Point, b: Point, squared: boolean): number;
+export declare function onResize(callback: (width: number, height: number) => void): void;
+export declare function parse(text: string, ...rest: number[]): Point | undefined;
+export declare function root(): HTMLElement | null;
+export declare const version: string;
+
+export declare class Rect {
+    constructor();
+    constructor(width: number, height: number);
+}
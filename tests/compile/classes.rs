@@ -0,0 +1,5 @@
+fn main() {
+    // Overloaded constructors are distinct constructors
+    let _: fn() -> Rect = Rect::new;
+    let _: fn(f64, f64) -> Rect = Rect::new_with_width_and_height;
+}
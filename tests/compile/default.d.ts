@@ -0,0 +1,4 @@
+declare class Widget {
+    render(): void;
+}
+export default Widget;
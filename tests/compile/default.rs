@@ -0,0 +1,4 @@
+fn main() {
+    // The default export is usable by the name `default`
+    let _: Option<&default> = None;
+}
@@ -0,0 +1,4 @@
+export interface Empty {}
+export declare class Nothing {}
+export declare namespace hollow {}
+export declare function f(e: Empty, n: Nothing): void;
@@ -0,0 +1,22 @@
+export declare enum Flags {
+    None = 0,
+    A = 1 << 0,
+    B = 1 << 1,
+    AB = A | B,
+}
+
+export declare enum Direction {
+    Up = "up",
+    Down = "down",
+}
+
+export type Mode = "light" | "dark";
+
+export declare namespace settings {
+    enum Level {
+        Low,
+        High,
+    }
+    function level(): Level;
+    function setMode(mode: Mode): void;
+}
@@ -0,0 +1,12 @@
+fn main() {
+    for direction in [Direction::Up, Direction::Down] {
+        assert_eq!(
+            Direction::from_js_string(direction.as_js_string()),
+            Some(direction)
+        );
+    }
+    assert_eq!(Direction::from_js_string("up"), Some(Direction::Up));
+    assert_eq!(Direction::from_js_string("left"), None);
+    assert_eq!(Direction::Down.as_js_string(), "down");
+    assert_eq!(Flags::AB as u32, 3);
+}
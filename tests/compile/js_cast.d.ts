@@ -0,0 +1,9 @@
+export declare class C {
+    m(): void;
+}
+export interface I {
+    x: number;
+}
+export declare namespace ns {
+    class D {}
+}
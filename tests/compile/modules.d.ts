@@ -0,0 +1,13 @@
+declare module "pkg/sub" {
+    export function a(): void;
+}
+declare module "pkg-sub" {
+    export function b(): void;
+}
+declare module "pkg/sub" {
+    export function c(): void;
+}
+declare module "*.css" {
+    const classes: { [key: string]: string };
+    export default classes;
+}
@@ -0,0 +1,3 @@
+export declare function draw(pixels: Uint8ClampedArray, width: number): Uint8ClampedArray;
+export declare function sum(values: BigInt64Array): BigUint64Array;
+export declare function scale(values: Float32Array, by: number): Float32Array | null;
@@ -0,0 +1,6 @@
+use wasm_bindgen::Clamped;
+
+fn main() {
+    let _: fn(Clamped<&[u8]>, f64) -> Clamped<Vec<u8>> = draw;
+    let _: fn(&[i64]) -> Vec<u64> = sum;
+}
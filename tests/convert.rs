@@ -0,0 +1,1151 @@
+//! Bindings generated for declarations
+
+use std::collections::BTreeSet;
+
+use wasm_bindgen_ts_decl::{
+    convert_dts_source, convert_dts_to_writer, set_config, take_stats, Config, Construct,
+};
+
+fn convert(src: &str) -> String {
+    convert_with(Config::default(), src)
+}
+
+fn convert_with(config: Config, src: &str) -> String {
+    set_config(config);
+    prettyplease::unparse(&convert_dts_source(src).unwrap())
+}
+
+/// Drop whitespace & trailing commas so expectations don't depend on line wrapping
+fn squash(s: &str) -> String {
+    let s: String = s.split_whitespace().collect();
+    s.replace(",)", ")").replace(",}", "}").replace(",]", "]")
+}
+
+#[track_caller]
+fn assert_has(out: &str, expected: &str) {
+    assert!(
+        squash(out).contains(&squash(expected)),
+        "expected `{expected}` in:\n{out}"
+    );
+}
+
+#[track_caller]
+fn assert_lacks(out: &str, unexpected: &str) {
+    assert!(
+        !squash(out).contains(&squash(unexpected)),
+        "unexpected `{unexpected}` in:\n{out}"
+    );
+}
+
+#[test]
+fn overloads_of_differing_arity() {
+    let out = convert(
+        "export declare function pick(a: number): number;
+        export declare function pick(a: number, b: string): number;
+        export declare function pick(a: number): number;",
+    );
+    assert_has(
+        &out,
+        "pub fn pick(a: ::core::primitive::f64) -> ::core::primitive::f64;",
+    );
+    assert_has(
+        &out,
+        r#"#[wasm_bindgen(js_name = "pick")]
+        pub fn pick_1(a: ::core::primitive::f64, b: ::std::string::String) -> ::core::primitive::f64;"#,
+    );
+    // The repeated signature is dropped rather than renamed
+    assert_lacks(&out, "pick_2");
+}
+
+#[test]
+fn thread_local_globals() {
+    let src = "export declare const localStorage: Storage;
+        export declare const version: string;";
+    let out = convert_with(
+        Config {
+            thread_local_globals: true,
+            ..Config::default()
+        },
+        src,
+    );
+    assert_has(
+        &out,
+        r#"#[wasm_bindgen(js_name = "localStorage", thread_local_v2)]
+        pub static LOCAL_STORAGE: Storage;"#,
+    );
+    // Only globals with a web_sys type are thread local
+    assert_has(&out, "pub static version: ::std::string::String;");
+
+    let out = convert_with(
+        Config {
+            thread_local_globals: true,
+            target: "0.2.90".parse().unwrap(),
+            ..Config::default()
+        },
+        src,
+    );
+    assert_has(
+        &out,
+        r#"#[wasm_bindgen(js_name = "localStorage", thread_local)]"#,
+    );
+
+    let out = convert(src);
+    assert_has(&out, "pub static localStorage: Storage;");
+}
+
+#[test]
+fn jsdoc_integer_params() {
+    let out = convert(
+        "/**
+         * Scale by {@link Factor}
+         * @param {int} x the x
+         * @returns {long}
+         */
+        export declare function scale(x: number, y: number): number;",
+    );
+    assert_has(
+        &out,
+        "pub fn scale(x: ::core::primitive::i32, y: ::core::primitive::f64) -> ::core::primitive::i64;",
+    );
+}
+
+#[test]
+fn jsdoc_inline_tags_arent_block_tags() {
+    let src = "/**
+         * Unlike {@internal hidden} ones, this is public
+         */
+        export declare function open(): void;";
+    let out = convert_with(
+        Config {
+            strip_internal: true,
+            ..Config::default()
+        },
+        src,
+    );
+    assert_has(&out, "pub fn open();");
+}
+
+#[test]
+fn internal_declarations_are_stripped() {
+    let src = "/** @internal */
+        export declare function hidden(): void;
+        export declare function shown(): void;
+        export declare class C {
+            /**
+             * Not for you
+             * @internal
+             */
+            secret(): void;
+            open(): void;
+            /** @internal */
+            state: number;
+        }
+        /** @internal */
+        export declare class Hidden {}
+        export interface I {
+            /** @internal */
+            x: number;
+            y: number;
+        }";
+    let out = convert_with(
+        Config {
+            strip_internal: true,
+            ..Config::default()
+        },
+        src,
+    );
+    assert_has(&out, "pub fn shown();");
+    assert_has(&out, "pub fn open(this: &C);");
+    assert_has(&out, "pub fn y(this: &I) -> ::core::primitive::f64;");
+    for name in ["hidden", "secret", "state", "Hidden", "fn x("] {
+        assert_lacks(&out, name);
+    }
+
+    // Kept unless they're stripped
+    let out = convert(src);
+    for name in ["hidden", "secret", "state", "Hidden", "fn x("] {
+        assert_has(&out, name);
+    }
+}
+
+#[test]
+fn destructured_params() {
+    let out = convert(
+        "export interface Point { x: number; y: number }
+        export declare function f({ x, y }: Point, { z }): void;",
+    );
+    assert_has(
+        &out,
+        "pub fn f(arg0: Point, arg1: ::wasm_bindgen::JsValue);",
+    );
+}
+
+#[test]
+fn defaulted_params_are_optional() {
+    let out = convert("export declare function f(x: number = 5, y: number): void;");
+    assert_has(
+        &out,
+        "pub fn f(x: ::std::option::Option<::core::primitive::f64>, y: ::core::primitive::f64);",
+    );
+}
+
+#[test]
+fn trivially_true_conditional_types() {
+    let out = convert(
+        "export declare function a(): string extends unknown ? number : boolean;
+        export declare function b(): string extends string ? number : boolean;
+        export declare function c(): any extends string ? number : boolean;
+        export declare function d(): unknown extends string ? number : boolean;",
+    );
+    assert_has(&out, "pub fn a() -> ::core::primitive::f64;");
+    assert_has(&out, "pub fn b() -> ::core::primitive::f64;");
+    // `any` takes both branches & `unknown` takes the false one
+    assert_has(&out, "pub fn c() -> ::wasm_bindgen::JsValue;");
+    assert_has(&out, "pub fn d() -> ::wasm_bindgen::JsValue;");
+}
+
+#[test]
+fn evaluated_enum_initializers() {
+    let out = convert("export declare enum Flags { A = 1 << 2, B = A, C = A | 1, D, E = C + 2 }");
+    assert_has(&out, "pub enum Flags { A = 4, C = 5, D = 6, E = 7 }");
+    // `B` has the same value as `A`
+    assert_has(&out, "pub const B: Flags = Flags::A;");
+}
+
+#[test]
+fn namespaced_enums_are_exported_with_their_namespace() {
+    let out = convert(
+        "export declare enum Kind { A }
+        export declare namespace a {
+            enum Kind { X }
+            namespace b { enum Kind { Y } }
+        }",
+    );
+    assert_has(
+        &out,
+        "#[wasm_bindgen] #[derive(Clone, Copy, Debug, PartialEq, Eq)] pub enum Kind { A = 0 }",
+    );
+    assert_has(&out, r#"#[wasm_bindgen(js_name = "a_Kind")]"#);
+    assert_has(&out, r#"#[wasm_bindgen(js_name = "a_b_Kind")]"#);
+}
+
+#[test]
+fn accessors_of_different_types() {
+    let out = convert(
+        "export declare class C {
+            get x(): string;
+            set x(value: string | number);
+        }",
+    );
+    assert_has(
+        &out,
+        r#"#[wasm_bindgen(js_name = "x", getter, method)]
+        pub fn get_x(this: &C) -> ::std::string::String;"#,
+    );
+    assert_has(
+        &out,
+        "#[wasm_bindgen(setter, method)]
+        pub fn set_x(this: &C, value: ::wasm_bindgen::JsValue);",
+    );
+}
+
+#[test]
+fn convert_to_writer() {
+    set_config(Config::default());
+    let mut out: Vec<u8> = vec![];
+    convert_dts_to_writer("export declare function f(x: number): void;", &mut out).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "use ::wasm_bindgen::prelude::wasm_bindgen;
+#[wasm_bindgen]
+extern \"C\" {
+    pub fn f(x: ::core::primitive::f64);
+}
+"
+    );
+}
+
+#[test]
+fn declared_modules() {
+    let out = convert(
+        r#"declare module "pkg/sub" {
+            export function a(): void;
+        }
+        declare module "pkg-sub" {
+            export function b(): void;
+        }
+        declare module "pkg/sub" {
+            export function c(): void;
+        }
+        declare module "*.css" {
+            const classes: { [key: string]: string };
+            export default classes;
+        }"#,
+    );
+    assert_has(
+        &out,
+        r#"pub mod pkg_subMod {
+            use super::*;
+            #[wasm_bindgen(module = "pkg/sub")]
+            extern "C" {
+                pub fn a();
+            }
+            #[wasm_bindgen(module = "pkg/sub")]
+            extern "C" {
+                pub fn c();
+            }
+        }"#,
+    );
+    assert_has(
+        &out,
+        r#"pub mod pkg_sub_1Mod {
+            use super::*;
+            #[wasm_bindgen(module = "pkg-sub")]"#,
+    );
+    assert_lacks(&out, "css");
+}
+
+#[test]
+fn symbol_keys() {
+    let out = convert(
+        r#"export declare class List {
+            [Symbol.iterator](): Iterator<number>;
+            [Symbol.asyncIterator](): AsyncIterator<number>;
+            [Symbol.hasInstance](x: unknown): boolean;
+            ["size"]: number;
+        }"#,
+    );
+    assert_has(&out, "pub fn size(this: &List) -> ::core::primitive::f64;");
+    assert_has(
+        &out,
+        "#[wasm_bindgen(method)]
+        /// `[Symbol.iterator]` is bound as `values`
+        pub fn values(this: &List) -> Iterator;",
+    );
+    assert_has(
+        &out,
+        "#[wasm_bindgen(method)]
+        /// `[Symbol.asyncIterator]` is bound as `iter`
+        pub fn iter(this: &List) -> AsyncIterator;",
+    );
+    // Other symbols have no name to bind them by
+    assert_lacks(&out, "hasInstance");
+    assert_lacks(&out, "has_instance");
+}
+
+#[test]
+fn object_typed_variables_dont_reuse_type_names() {
+    let out = convert(
+        "export interface ConfigType { a: number }
+        export declare const config: { port: number };
+        export declare const Config: { host: string };",
+    );
+    assert_has(&out, "pub type ConfigType;");
+    assert_has(&out, "pub type ConfigType1;");
+    assert_has(&out, "pub static config: ConfigType1;");
+    assert_has(&out, "pub type ConfigType2;");
+    assert_has(&out, "pub static Config: ConfigType2;");
+}
+
+#[test]
+fn default_exports_and_imports() {
+    let out = convert(
+        "declare class Widget { render(): void }
+        export default Widget;",
+    );
+    assert_has(&out, "pub use self::Widget as default;");
+
+    let out = convert(
+        r#"import W from "./widget";
+        import { default as V } from "./widget";
+        export declare function make(): W;"#,
+    );
+    assert_has(&out, "pub use super::widgetMod::default as W;");
+    assert_has(&out, "pub use super::widgetMod::default as V;");
+    // `default` is only a keyword in some positions, so neither is a raw ident
+    syn::parse_file(&out).unwrap();
+}
+
+#[test]
+fn callable_constructable_interfaces() {
+    let out = convert(
+        "export interface JQuery {
+            (selector: string): JQuery;
+            new (): JQuery;
+            length: number;
+        }",
+    );
+    assert_has(
+        &out,
+        "#[wasm_bindgen(method)]
+        pub fn call(this: &JQuery, this_arg: ::wasm_bindgen::JsValue, selector: ::std::string::String) -> JQuery;",
+    );
+    assert_has(&out, "#[wasm_bindgen(constructor)] pub fn new() -> JQuery;");
+    assert_has(
+        &out,
+        "#[wasm_bindgen(getter, method)] pub fn length(this: &JQuery) -> ::core::primitive::f64;",
+    );
+}
+
+#[test]
+fn declared_functions_in_namespaces() {
+    let out = convert(
+        "export declare namespace ns {
+            declare function hidden(x: number): void;
+            export function shown(): void;
+        }",
+    );
+    assert_has(
+        &out,
+        r#"#[wasm_bindgen(js_namespace = ["ns"])]
+        pub fn hidden(x: ::core::primitive::f64);"#,
+    );
+    assert_has(
+        &out,
+        r#"#[wasm_bindgen(js_namespace = ["ns"])]
+        pub fn shown();"#,
+    );
+}
+
+#[test]
+fn uses_of_abified_types_are_removed() {
+    let out = convert(
+        "export declare function f(a: Storage[][]): void;
+        export declare function g(): Document;",
+    );
+    assert_has(&out, "pub fn f(a: ::wasm_bindgen::JsValue);");
+    assert_lacks(&out, "Storage");
+    assert_has(&out, "use ::web_sys::Document;");
+}
+
+#[test]
+fn enum_members_resolve_in_scope() {
+    let out = convert(
+        "export declare namespace Kind { interface X {} }
+        export declare function f(k: Kind.X): void;
+        export declare namespace a {
+            enum Kind { X, Y }
+            function g(k: Kind.X): void;
+        }",
+    );
+    // `a.Kind` isn't in scope at the root, where `Kind` is a namespace
+    assert_lacks(&out, "pub fn f(k: Kind);");
+    assert_has(&out, "pub fn g(k: Kind);");
+}
+
+#[test]
+fn empty_declarations() {
+    let out = convert(
+        "export interface Empty {}
+        export declare class Nothing {}
+        export declare namespace hollow {}
+        export declare function f(e: Empty, n: Nothing): void;",
+    );
+    assert_has(
+        &out,
+        r#"extern "C" {
+            pub type Empty;
+            pub type Nothing;
+            pub fn f(e: Empty, n: Nothing);
+        }"#,
+    );
+    assert_has(&out, "pub mod hollowMod {}");
+}
+
+#[test]
+fn private_members_and_parameter_properties() {
+    let out = convert(
+        "export declare class Counter {
+            #count: number;
+            count: number;
+            #bump(): void;
+            bump(): void;
+            constructor(public start: number);
+        }
+        export declare abstract class Shape {
+            constructor(readonly sides: number);
+        }
+        export declare class Singleton {
+            private constructor(public id: string);
+        }",
+    );
+    // `#count` & `#bump` don't get the public members renamed
+    assert_has(
+        &out,
+        "#[wasm_bindgen(getter, method)]
+        pub fn count(this: &Counter) -> ::core::primitive::f64;",
+    );
+    assert_has(&out, "pub fn bump(this: &Counter);");
+    assert_lacks(&out, "count_1");
+    assert_lacks(&out, "bump_1");
+    assert_has(
+        &out,
+        "pub fn start(this: &Counter) -> ::core::primitive::f64;",
+    );
+    // Neither can be constructed, but the properties are still declared
+    assert_has(
+        &out,
+        "pub fn sides(this: &Shape) -> ::core::primitive::f64;",
+    );
+    assert_lacks(&out, "set_sides");
+    assert_has(
+        &out,
+        "pub fn id(this: &Singleton) -> ::std::string::String;",
+    );
+    assert_lacks(&out, "-> Shape;");
+    assert_lacks(&out, "-> Singleton;");
+}
+
+#[test]
+fn variance_modifiers() {
+    let out = convert(
+        "export interface Producer<out T> { get(): T; }
+        export interface Consumer<in T> { set(value: T): void; }
+        export interface Both<in out T> { swap(value: T): T; }
+        export declare function f(p: Producer<number>, c: Consumer<string>): void;",
+    );
+    assert_has(
+        &out,
+        "pub type Producer;
+        #[wasm_bindgen(method)]
+        pub fn get(this: &Producer) -> ::wasm_bindgen::JsValue;",
+    );
+    assert_has(
+        &out,
+        "pub fn set(this: &Consumer, value: ::wasm_bindgen::JsValue);",
+    );
+    assert_has(
+        &out,
+        "pub fn swap(this: &Both, value: ::wasm_bindgen::JsValue) -> ::wasm_bindgen::JsValue;",
+    );
+    assert_has(&out, "pub fn f(p: Producer, c: Consumer);");
+}
+
+#[test]
+fn final_methods_are_only_for_classes() {
+    let out = convert_with(
+        Config {
+            final_methods: true,
+            ..Config::default()
+        },
+        "export interface Shape { area(): number; readonly name: string; }
+        export declare class Square {
+            area(): number;
+            readonly name: string;
+            constructor(side: number);
+        }",
+    );
+    assert_has(
+        &out,
+        "#[wasm_bindgen(method)]
+        pub fn area(this: &Shape) -> ::core::primitive::f64;
+        #[wasm_bindgen(getter, method)]
+        pub fn name(this: &Shape) -> ::std::string::String;",
+    );
+    assert_has(
+        &out,
+        "#[wasm_bindgen(final, method)]
+        pub fn area(this: &Square) -> ::core::primitive::f64;
+        #[wasm_bindgen(final, getter, method)]
+        pub fn name(this: &Square) -> ::std::string::String;",
+    );
+    // Constructors aren't looked up on the prototype
+    assert_has(
+        &out,
+        "#[wasm_bindgen(constructor)]
+        pub fn new(side: ::core::primitive::f64) -> Square;",
+    );
+}
+
+#[test]
+fn generic_aliases_are_erased_where_used() {
+    let out = convert(
+        "export type Box<T> = { value: T };
+        export declare const b: Box<number>;
+        export declare function f(b: Box<string>): Box<number>;",
+    );
+    assert_has(
+        &out,
+        "pub type Box;
+        #[wasm_bindgen(getter, method)]
+        pub fn value(this: &Box) -> ::wasm_bindgen::JsValue;",
+    );
+    assert_has(&out, "pub static b: Box;");
+    assert_has(&out, "pub fn f(b: Box) -> Box;");
+}
+
+#[test]
+fn recursive_object_typed_variables() {
+    let out = convert("export declare const node: { next: typeof node; child: { x: number } };");
+    assert_has(&out, "pub static node: NodeType;");
+    // Only the outermost literal is bound, & `typeof` isn't followed
+    assert_has(
+        &out,
+        "pub fn next(this: &NodeType) -> ::wasm_bindgen::JsValue;",
+    );
+    assert_has(
+        &out,
+        "pub fn child(this: &NodeType) -> ::wasm_bindgen::JsValue;",
+    );
+    assert_lacks(&out, "pub fn x(");
+}
+
+#[test]
+fn keyword_method_names() {
+    let out = convert(
+        "export declare class K {
+            type(): void;
+            match(): void;
+            loop(): void;
+            move(): void;
+            ref(): void;
+            box(): void;
+        }",
+    );
+    for keyword in ["type", "match", "loop", "move", "ref", "box"] {
+        assert_has(
+            &out,
+            &format!(
+                r#"#[wasm_bindgen(js_name = "{keyword}", method)]
+                pub fn r#{keyword}(this: &K);"#
+            ),
+        );
+    }
+}
+
+#[test]
+fn stats() {
+    take_stats();
+    convert(
+        "export declare class A { a(): void; [Symbol.hasInstance](): void; }
+        export interface I { x: string | number; }
+        export declare function f(): void;
+        export declare function g(): void;
+        export declare enum E { X }",
+    );
+    let stats = take_stats();
+    assert_eq!(
+        (
+            stats.classes,
+            stats.interfaces,
+            stats.functions,
+            stats.enums
+        ),
+        (1, 1, 2, 1)
+    );
+    let sources = |constructs: &BTreeSet<Construct>| {
+        constructs
+            .iter()
+            .map(|construct| construct.source.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(sources(&stats.fallbacks), ["string | number"]);
+    assert_eq!(sources(&stats.skipped), ["Symbol.hasInstance"]);
+    // Taking them resets them
+    assert_eq!(take_stats(), Default::default());
+}
+
+#[test]
+fn string_enum_conversions() {
+    let out = convert(r#"export declare enum Direction { Up = "up", Down = "down" }"#);
+    assert_has(
+        &out,
+        r#"pub fn from_js_string(s: &::core::primitive::str) -> ::std::option::Option<Self> {
+            match s {
+                "up" => ::std::option::Option::Some(Self::Up),
+                "down" => ::std::option::Option::Some(Self::Down),
+                _ => ::std::option::Option::None,
+            }
+        }"#,
+    );
+    assert_has(
+        &out,
+        r#"pub fn as_js_string(&self) -> &'static ::core::primitive::str {
+            #[allow(unreachable_patterns)]
+            match self {
+                Self::Up => "up",
+                Self::Down => "down",
+                _ => ::core::panic!("invalid enum value passed"),
+            }
+        }"#,
+    );
+    // Numeric enums are converted with `as`
+    let out = convert("export declare enum Flags { A = 1 }");
+    assert_lacks(&out, "from_js_string");
+}
+
+#[test]
+fn custom_web_sys_path() {
+    let out = convert_with(
+        Config {
+            web_sys_path: syn::parse_str("::my_web").unwrap(),
+            ..Config::default()
+        },
+        "export declare function f(d: Document): HTMLElement;",
+    );
+    assert_has(&out, "use ::my_web::Document;");
+    assert_has(&out, "use ::my_web::HtmlElement;");
+    assert_lacks(&out, "web_sys");
+}
+
+#[test]
+fn fallbacks_are_noted_once() {
+    let out = convert_with(
+        Config {
+            note_fallbacks: true,
+            ..Config::default()
+        },
+        "export declare function f(a: Storage[][], b: string | number): void;
+        export declare function g(a: Storage[][], b: Storage[][], c: Map<string, number>): void;",
+    );
+    assert_has(
+        &out,
+        "/// `string | number` is bound as `JsValue`
+        /// `::std::boxed::Box<[::std::boxed::Box<[Storage]>]>` is bound as `JsValue`
+        pub fn f(",
+    );
+    assert_has(
+        &out,
+        "[Storage]>]>` is bound as `JsValue`
+        pub fn g(",
+    );
+    assert_eq!(out.matches("is bound as").count(), 3, "{out}");
+}
+
+#[test]
+fn overloaded_constructors() {
+    let out = convert(
+        "export declare class P {
+            constructor();
+            constructor(a: number);
+            constructor(a: number, b: string);
+        }",
+    );
+    // Each is a `constructor`, which wasm_bindgen allows any number of by distinct names
+    assert_has(
+        &out,
+        "#[wasm_bindgen(constructor)]
+        pub fn new() -> P;
+        #[wasm_bindgen(constructor)]
+        pub fn new_with_a(a: ::core::primitive::f64) -> P;
+        #[wasm_bindgen(constructor)]
+        pub fn new_with_a_and_b(a: ::core::primitive::f64, b: ::std::string::String) -> P;",
+    );
+    assert_lacks(&out, "static_method_of");
+}
+
+#[test]
+fn structural_interfaces() {
+    let src =
+        "export interface Shape { area(): number; readonly name: string; [key: string]: unknown; }
+        export declare class Square { area(): number; }";
+    let out = convert_with(
+        Config {
+            structural_interfaces: true,
+            ..Config::default()
+        },
+        src,
+    );
+    assert_has(
+        &out,
+        "#[wasm_bindgen(structural, method)]
+        pub fn area(this: &Shape) -> ::core::primitive::f64;
+        #[wasm_bindgen(structural, getter, method)]
+        pub fn name(this: &Shape) -> ::std::string::String;",
+    );
+    assert_has(
+        &out,
+        "#[wasm_bindgen(method)]
+        pub fn area(this: &Square) -> ::core::primitive::f64;",
+    );
+    assert_lacks(&convert(src), "(structural,");
+}
+
+#[test]
+fn namespace_exports() {
+    let out = convert(
+        r#"export * as ns from "./a";
+        export * as type from "../b/c";"#,
+    );
+    assert_has(&out, "pub use super::aMod as ns;");
+    assert_has(&out, "pub use super::super::bMod::cMod as r#type;");
+}
+
+#[test]
+fn getters_returning_this() {
+    let out = convert(
+        "export interface I { get me(): this; get self(): this; }
+        export declare class C { get self(): this; set self(value: this); }",
+    );
+    assert_has(
+        &out,
+        r#"#[wasm_bindgen(js_name = "me", getter, method)]
+        pub fn get_me(this: &I) -> I;"#,
+    );
+    // The prefix makes `self` a valid name already
+    assert_has(
+        &out,
+        r#"#[wasm_bindgen(js_name = "self", getter, method)]
+        pub fn get_self(this: &I) -> I;"#,
+    );
+    assert_has(&out, "pub fn get_self(this: &C) -> C;");
+    assert_has(
+        &out,
+        "#[wasm_bindgen(setter, method)]
+        pub fn set_self(this: &C, value: C);",
+    );
+    assert_lacks(&out, "Self");
+    assert_lacks(&out, "self_rs");
+}
+
+#[test]
+fn numeric_keys() {
+    let out = convert(
+        r#"export interface T {
+            0: string;
+            "data-id": number;
+            get 1(): number;
+            set 1(v: number);
+        }"#,
+    );
+    assert_has(
+        &out,
+        r#"#[wasm_bindgen(js_name = "0", getter, method)]
+        pub fn _0(this: &T) -> ::std::string::String;
+        #[wasm_bindgen(js_name = "0", setter, method)]
+        pub fn set_0(this: &T, value: ::std::string::String);"#,
+    );
+    assert_has(
+        &out,
+        r#"#[wasm_bindgen(js_name = "data-id", getter, method)]
+        pub fn data_id(this: &T) -> ::core::primitive::f64;"#,
+    );
+    assert_has(
+        &out,
+        r#"#[wasm_bindgen(js_name = "1", getter, method)]
+        pub fn get_1(this: &T) -> ::core::primitive::f64;"#,
+    );
+    assert_has(&out, "pub fn set_1(this: &T, v: ::core::primitive::f64);");
+    assert_lacks(&out, "__");
+}
+
+#[test]
+fn static_methods_returning_this() {
+    let out = convert("export declare class P { static create(): this; }");
+    assert_has(
+        &out,
+        "#[wasm_bindgen(static_method_of = P)]
+        pub fn create() -> P;",
+    );
+    assert_lacks(&out, "Self");
+}
+
+#[test]
+fn interface_property_receivers() {
+    let out = convert(
+        "export interface Base { readonly id: number; }
+        export interface Iface extends Base { label: string; }",
+    );
+    assert_has(
+        &out,
+        "#[wasm_bindgen(getter, method)]
+        pub fn label(this: &Iface) -> ::std::string::String;
+        #[wasm_bindgen(setter, method)]
+        pub fn set_label(this: &Iface, value: ::std::string::String);",
+    );
+    assert_has(&out, "pub fn id(this: &Base) -> ::core::primitive::f64;");
+}
+
+#[test]
+fn nullable_optional_properties() {
+    let out = convert("export interface I { x?: string | null; }");
+    // Optional & nullable are the same `Option`
+    assert_has(
+        &out,
+        "#[wasm_bindgen(getter, method)]
+        pub fn x(this: &I) -> ::std::option::Option<::std::string::String>;
+        #[wasm_bindgen(setter, method)]
+        pub fn set_x(this: &I, value: ::std::option::Option<::std::string::String>);",
+    );
+    assert_lacks(&out, "Option<::std::option::Option");
+}
+
+#[test]
+fn callback_params_and_properties() {
+    let out = convert(
+        "export declare function on(cb: (e: number) => void): void;
+        export declare function make(): (e: number) => void;
+        export interface H { handler: (e: number) => void; }",
+    );
+    // Borrowed for the call only
+    assert_has(&out, "pub fn on(cb: &dyn Fn(::core::primitive::f64));");
+    // Kept by JS, or handed to Rust
+    assert_has(&out, "pub fn make() -> Function;");
+    assert_has(
+        &out,
+        "#[wasm_bindgen(getter, method)]
+        pub fn handler(this: &H) -> Function;
+        #[wasm_bindgen(setter, method)]
+        pub fn set_handler(this: &H, value: Function);",
+    );
+    assert_has(&out, "use ::js_sys::Function;");
+}
+
+#[test]
+fn function_typed_properties() {
+    let out = convert(
+        "export declare class K {
+            callback: (x: string) => void;
+            onDone?: () => string;
+            static hook: () => void;
+        }",
+    );
+    assert_has(&out, "pub fn callback(this: &K) -> Function;");
+    assert_has(&out, "pub fn set_callback(this: &K, value: Function);");
+    assert_has(
+        &out,
+        "pub fn onDone(this: &K) -> ::std::option::Option<Function>;",
+    );
+    assert_has(
+        &out,
+        "#[wasm_bindgen(getter, static_method_of = K)]
+        pub fn hook() -> Function;",
+    );
+    assert_lacks(&out, "dyn Fn");
+}
+
+#[test]
+fn clamped_typed_array_slices() {
+    let out = convert_with(
+        Config {
+            typed_array_slices: true,
+            ..Config::default()
+        },
+        "export declare function draw(pixels: Uint8ClampedArray): Uint8ClampedArray;",
+    );
+    assert_has(
+        &out,
+        "pub fn draw(
+            pixels: ::wasm_bindgen::Clamped<&[::core::primitive::u8]>,
+        ) -> ::wasm_bindgen::Clamped<::std::vec::Vec<::core::primitive::u8>>;",
+    );
+    assert_lacks(&out, "Uint8ClampedArray");
+}
+
+#[test]
+fn big_int_typed_array_slices() {
+    let out = convert_with(
+        Config {
+            typed_array_slices: true,
+            ..Config::default()
+        },
+        "export declare function sum(values: BigInt64Array): BigUint64Array;",
+    );
+    assert_has(
+        &out,
+        "pub fn sum(values: &[::core::primitive::i64]) -> ::std::vec::Vec<::core::primitive::u64>;",
+    );
+    assert_lacks(&out, "BigInt64Array");
+    assert_lacks(&out, "BigUint64Array");
+}
+
+#[test]
+fn vec_returns() {
+    let src = "export declare function list(): number[];
+        export declare function maybe(): number[] | undefined;
+        /** @throws {Error} */
+        export declare function risky(): number[];
+        export declare function take(xs: number[]): void;";
+    let out = convert_with(
+        Config {
+            vec_returns: true,
+            catch_throws: true,
+            ..Config::default()
+        },
+        src,
+    );
+    assert_has(
+        &out,
+        "pub fn list() -> ::std::vec::Vec<::core::primitive::f64>;",
+    );
+    assert_has(
+        &out,
+        "pub fn maybe() -> ::std::option::Option<::std::vec::Vec<::core::primitive::f64>>;",
+    );
+    assert_has(
+        &out,
+        "pub fn risky() -> ::std::result::Result<
+            ::std::vec::Vec<::core::primitive::f64>,
+            ::wasm_bindgen::JsValue,
+        >;",
+    );
+    // Params are still boxed slices
+    assert_has(
+        &out,
+        "pub fn take(xs: ::std::boxed::Box<[::core::primitive::f64]>);",
+    );
+    assert_lacks(&convert(src), "Vec");
+}
+
+#[test]
+fn catch_throws() {
+    let src = "/**
+         * @throws {TypeError} when `s` isn't a number
+         */
+        export declare function parse(s: string): number;
+        /** @throws */
+        export declare function go(): void;
+        export declare function safe(): void;
+        export declare class C {
+            /** @throws {Error} */
+            m(): string;
+        }";
+    let out = convert_with(
+        Config {
+            catch_throws: true,
+            ..Config::default()
+        },
+        src,
+    );
+    assert_has(
+        &out,
+        "#[wasm_bindgen(catch)]
+        pub fn parse(
+            s: ::std::string::String,
+        ) -> ::std::result::Result<::core::primitive::f64, ::wasm_bindgen::JsValue>;",
+    );
+    assert_has(
+        &out,
+        "#[wasm_bindgen(catch)]
+        pub fn go() -> ::std::result::Result<(), ::wasm_bindgen::JsValue>;",
+    );
+    assert_has(&out, "pub fn safe();");
+    assert_has(
+        &out,
+        "#[wasm_bindgen(catch, method)]
+        pub fn m(
+            this: &C,
+        ) -> ::std::result::Result<::std::string::String, ::wasm_bindgen::JsValue>;",
+    );
+    assert_lacks(&convert(src), "catch");
+}
+
+#[test]
+fn renamed_classes_keep_their_js_class() {
+    let out = convert(
+        "export declare class URLParser {
+            constructor();
+            parse(s: string): void;
+            static make(): URLParser;
+        }
+        export declare class Plain { m(): void; }",
+    );
+    assert_has(
+        &out,
+        r#"#[wasm_bindgen(js_name = "URLParser")]
+        pub type UrlParser;
+        #[wasm_bindgen(js_class = "URLParser", constructor)]
+        pub fn new() -> UrlParser;
+        #[wasm_bindgen(js_class = "URLParser", method)]
+        pub fn parse(this: &UrlParser, s: ::std::string::String);
+        #[wasm_bindgen(js_class = "URLParser", static_method_of = UrlParser)]
+        pub fn make() -> UrlParser;"#,
+    );
+    assert_lacks(&out, r#"js_class = "Plain""#);
+}
+
+#[test]
+fn umd_globals() {
+    let src = "export as namespace MyLib;
+        export declare function f(): void;
+        export declare const v: number;";
+    let out = convert(src);
+    assert_has(
+        &out,
+        r#"#[wasm_bindgen(js_namespace = ["MyLib"])]
+        pub fn f();
+        #[wasm_bindgen(js_namespace = ["MyLib"])]
+        pub static v: ::core::primitive::f64;"#,
+    );
+
+    // Imported from the module rather than the global
+    let out = convert_with(
+        Config {
+            js_module: Some("./dist/lib.js".to_string()),
+            ..Config::default()
+        },
+        src,
+    );
+    assert_has(
+        &out,
+        r#"#[wasm_bindgen(module = "./dist/lib.js")]
+        extern "C" {
+            pub fn f();
+            pub static v: ::core::primitive::f64;
+        }"#,
+    );
+    assert_lacks(&out, "MyLib");
+}
+
+#[test]
+fn members_are_grouped_by_type() {
+    let out = convert(
+        "export interface A { a1(): void; }
+        export interface B { b1(): void; }
+        export declare function between(): void;
+        export interface A { a2(): void; }
+        export declare class C { c(): void; }
+        export interface B { b2(): void; }",
+    );
+    assert_has(
+        &out,
+        r#"extern "C" {
+            pub type A;
+            #[wasm_bindgen(method)]
+            pub fn a1(this: &A);
+            #[wasm_bindgen(method)]
+            pub fn a2(this: &A);
+            pub type B;
+            #[wasm_bindgen(method)]
+            pub fn b1(this: &B);
+            #[wasm_bindgen(method)]
+            pub fn b2(this: &B);
+            pub fn between();
+            pub type C;
+            #[wasm_bindgen(method)]
+            pub fn c(this: &C);
+        }"#,
+    );
+}
+
+#[test]
+fn js_cast_assertions() {
+    let src = "export declare class C {}
+        export declare namespace ns { class D {} }";
+    let out = convert_with(
+        Config {
+            assert_js_cast: true,
+            ..Config::default()
+        },
+        src,
+    );
+    assert_has(
+        &out,
+        "const _: () = {
+            const fn _assert<T: ::wasm_bindgen::JsCast>() {}
+            _assert::<C>();
+        };",
+    );
+    // In the module the type is declared in
+    assert_has(
+        &out,
+        "pub type D;
+            }
+            const _: () = {
+                const fn _assert<T: ::wasm_bindgen::JsCast>() {}
+                _assert::<D>();
+            };
+        }",
+    );
+    assert_lacks(&convert(src), "_assert");
+}